@@ -0,0 +1,544 @@
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::{Arc, Mutex, RwLock};
+use thiserror::Error;
+
+/// PBKDF2-HMAC-SHA256 rounds for new password hashes
+const HASH_ROUNDS: u32 = 600_000;
+const HASH_PREFIX: &str = "pbkdf2-sha256";
+/// Password checks allowed to run at once, so a flood of bad basic credentials
+/// cannot occupy every core
+const MAX_CONCURRENT_HASHES: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum Scope {
+    /// Dashboard, API reads, SSE and static assets
+    Read,
+    /// Create, edit, delete and promote ideas
+    WriteIdeas,
+    /// Create and archive changes and toggle tasks
+    WriteChanges,
+    /// Rewrite the source list
+    AdminConfig,
+}
+
+impl Scope {
+    pub fn name(self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::WriteIdeas => "write-ideas",
+            Scope::WriteChanges => "write-changes",
+            Scope::AdminConfig => "admin-config",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        [Scope::Read, Scope::WriteIdeas, Scope::WriteChanges, Scope::AdminConfig]
+            .into_iter()
+            .find(|scope| scope.name() == name.trim())
+    }
+}
+
+/// A static API token, sent as `Authorization: Bearer <token>`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TokenConfig {
+    pub name: String,
+    pub token: String,
+    pub scopes: Vec<Scope>,
+}
+
+/// An HTTP basic user; generate `passwordHash` with `openspec-ui hash-password`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct UserConfig {
+    pub username: String,
+    pub password_hash: String,
+    pub scopes: Vec<Scope>,
+}
+
+/// Trust the identity a reverse proxy puts in a header, for requests from its address
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ProxyConfig {
+    #[serde(default = "default_user_header")]
+    pub user_header: String,
+    /// Optional header with comma-separated scopes; `scopes` applies when absent
+    #[serde(default)]
+    pub scopes_header: Option<String>,
+    #[serde(default = "default_proxy_scopes")]
+    pub scopes: Vec<Scope>,
+    #[serde(default = "default_trusted_proxies")]
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+fn default_user_header() -> String {
+    "X-Forwarded-User".to_string()
+}
+
+fn default_proxy_scopes() -> Vec<Scope> {
+    vec![Scope::Read]
+}
+
+fn default_trusted_proxies() -> Vec<IpAddr> {
+    vec![IpAddr::V4(Ipv4Addr::LOCALHOST), IpAddr::V6(Ipv6Addr::LOCALHOST)]
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AuthConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tokens: Vec<TokenConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub users: Vec<UserConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxyConfig>,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AuthError {
+    #[error("Authentication required")]
+    Unauthenticated,
+    #[error("Invalid credentials")]
+    InvalidCredentials,
+    #[error("Missing scope: {}", .0.name())]
+    Forbidden(Scope),
+}
+
+/// Who made a request, available to handlers as a request extension
+#[derive(Debug, Clone, PartialEq)]
+pub struct Principal {
+    pub name: String,
+    pub scopes: Vec<Scope>,
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn pbkdf2(password: &str, salt: &[u8], rounds: u32) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, rounds, &mut out);
+    out
+}
+
+/// `pbkdf2-sha256$<rounds>$<salt>$<hash>` with base64 salt and hash
+pub fn hash_password(password: &str) -> Result<String, getrandom::Error> {
+    hash_password_with_rounds(password, HASH_ROUNDS)
+}
+
+fn hash_password_with_rounds(password: &str, rounds: u32) -> Result<String, getrandom::Error> {
+    let mut salt = [0u8; 16];
+    getrandom::fill(&mut salt)?;
+    let hash = pbkdf2(password, &salt, rounds);
+    Ok(format!("{}${}${}${}", HASH_PREFIX, rounds, STANDARD.encode(salt), STANDARD.encode(hash)))
+}
+
+struct PasswordHash {
+    rounds: u32,
+    salt: Vec<u8>,
+    hash: Vec<u8>,
+}
+
+fn parse_password_hash(value: &str) -> Option<PasswordHash> {
+    let mut parts = value.split('$');
+    if parts.next()? != HASH_PREFIX {
+        return None;
+    }
+    let rounds = parts.next()?.parse().ok().filter(|r| *r > 0)?;
+    let salt = STANDARD.decode(parts.next()?).ok()?;
+    let hash = STANDARD.decode(parts.next()?).ok()?;
+    (parts.next().is_none() && hash.len() == 32).then_some(PasswordHash { rounds, salt, hash })
+}
+
+/// The scope a request needs, or None for endpoints that are always open
+pub fn required_scope(method: &Method, path: &str) -> Option<Scope> {
+    if path == "/api/health" || method == Method::OPTIONS {
+        return None;
+    }
+    if method == Method::GET || method == Method::HEAD || !path.starts_with("/api/") {
+        return Some(Scope::Read);
+    }
+    Some(if path.starts_with("/api/config") {
+        Scope::AdminConfig
+    } else if path.starts_with("/api/ideas") {
+        Scope::WriteIdeas
+    } else {
+        Scope::WriteChanges
+    })
+}
+
+/// Validated auth settings; without them the server is open
+pub struct Auth {
+    config: AuthConfig,
+    /// Digests of basic credentials that already passed PBKDF2, so browsers
+    /// re-sending them on every request stay cheap
+    verified: Mutex<HashSet<[u8; 32]>>,
+    hashing: tokio::sync::Semaphore,
+}
+
+impl Auth {
+    pub fn new(config: AuthConfig) -> Result<Self, String> {
+        if let Some(user) = config.users.iter().find(|u| parse_password_hash(&u.password_hash).is_none()) {
+            return Err(format!(
+                "user '{}' has an invalid passwordHash; generate one with `openspec-ui hash-password`",
+                user.username
+            ));
+        }
+        if let Some(token) = config.tokens.iter().find(|t| t.token.len() < 16) {
+            return Err(format!("token '{}' must be at least 16 characters", token.name));
+        }
+        Ok(Auth {
+            config,
+            verified: Mutex::new(HashSet::new()),
+            hashing: tokio::sync::Semaphore::new(MAX_CONCURRENT_HASHES),
+        })
+    }
+
+    fn bearer(&self, token: &str) -> Result<Principal, AuthError> {
+        self.config
+            .tokens
+            .iter()
+            .find(|t| constant_time_eq(t.token.as_bytes(), token.as_bytes()))
+            .map(|t| Principal {
+                name: t.name.clone(),
+                scopes: t.scopes.clone(),
+            })
+            .ok_or(AuthError::InvalidCredentials)
+    }
+
+    async fn basic(&self, encoded: &str) -> Result<Principal, AuthError> {
+        let decoded = STANDARD.decode(encoded.trim()).map_err(|_| AuthError::InvalidCredentials)?;
+        let decoded = String::from_utf8(decoded).map_err(|_| AuthError::InvalidCredentials)?;
+        let (username, password) = decoded.split_once(':').ok_or(AuthError::InvalidCredentials)?;
+        // Compare against every username so the lookup time does not reveal which exist;
+        // unknown usernames are rejected without hashing
+        let user = self
+            .config
+            .users
+            .iter()
+            .fold(None, |found, u| {
+                let matches = constant_time_eq(u.username.as_bytes(), username.as_bytes());
+                if matches { Some(u) } else { found }
+            })
+            .ok_or(AuthError::InvalidCredentials)?;
+        let stored = parse_password_hash(&user.password_hash).ok_or(AuthError::InvalidCredentials)?;
+
+        let digest: [u8; 32] = Sha256::new()
+            .chain_update(user.password_hash.as_bytes())
+            .chain_update(password.as_bytes())
+            .finalize()
+            .into();
+        let cached = self.verified.lock().unwrap().contains(&digest);
+        if !cached {
+            // Deliberately slow, so keep it off the async workers
+            let _permit = self.hashing.acquire().await.map_err(|_| AuthError::InvalidCredentials)?;
+            let password = password.to_string();
+            let (salt, rounds) = (stored.salt, stored.rounds);
+            let computed = tokio::task::spawn_blocking(move || pbkdf2(&password, &salt, rounds))
+                .await
+                .map_err(|_| AuthError::InvalidCredentials)?;
+            if !constant_time_eq(&computed, &stored.hash) {
+                return Err(AuthError::InvalidCredentials);
+            }
+            self.verified.lock().unwrap().insert(digest);
+        }
+        Ok(Principal {
+            name: user.username.clone(),
+            scopes: user.scopes.clone(),
+        })
+    }
+
+    fn proxy(&self, headers: &HeaderMap, peer: Option<IpAddr>) -> Option<Principal> {
+        let proxy = self.config.proxy.as_ref()?;
+        if !proxy.trusted_proxies.contains(&peer?) {
+            return None;
+        }
+        let user = headers.get(proxy.user_header.as_str())?.to_str().ok()?.trim();
+        if user.is_empty() {
+            return None;
+        }
+        let scopes = match proxy.scopes_header.as_deref().and_then(|h| headers.get(h)) {
+            Some(value) => value.to_str().ok()?.split(',').filter_map(Scope::parse).collect(),
+            None => proxy.scopes.clone(),
+        };
+        Some(Principal {
+            name: user.to_string(),
+            scopes,
+        })
+    }
+
+    /// Identify the caller from, in order: the Authorization header, a trusted
+    /// proxy header, or an `access_token` query parameter on the SSE endpoint
+    /// (EventSource cannot send headers)
+    pub async fn authenticate(
+        &self,
+        headers: &HeaderMap,
+        path: &str,
+        query: Option<&str>,
+        peer: Option<IpAddr>,
+    ) -> Result<Principal, AuthError> {
+        if let Some(value) = headers.get(header::AUTHORIZATION) {
+            let value = value.to_str().map_err(|_| AuthError::InvalidCredentials)?;
+            let (scheme, credentials) = value.split_once(' ').ok_or(AuthError::InvalidCredentials)?;
+            return match scheme.to_ascii_lowercase().as_str() {
+                "bearer" => self.bearer(credentials.trim()),
+                "basic" => self.basic(credentials).await,
+                _ => Err(AuthError::InvalidCredentials),
+            };
+        }
+        if let Some(principal) = self.proxy(headers, peer) {
+            return Ok(principal);
+        }
+        if path == "/api/events" {
+            let token = query
+                .into_iter()
+                .flat_map(|q| form_urlencoded::parse(q.as_bytes()))
+                .find_map(|(key, value)| (key == "access_token").then_some(value));
+            if let Some(token) = token {
+                return self.bearer(&token);
+            }
+        }
+        Err(AuthError::Unauthenticated)
+    }
+
+    pub async fn authorize(
+        &self,
+        method: &Method,
+        path: &str,
+        query: Option<&str>,
+        headers: &HeaderMap,
+        peer: Option<IpAddr>,
+    ) -> Result<Option<Principal>, AuthError> {
+        let Some(scope) = required_scope(method, path) else {
+            return Ok(None);
+        };
+        let principal = self.authenticate(headers, path, query, peer).await?;
+        if !principal.scopes.contains(&scope) {
+            return Err(AuthError::Forbidden(scope));
+        }
+        Ok(Some(principal))
+    }
+
+    fn challenge(&self) -> HeaderValue {
+        if self.config.users.is_empty() {
+            HeaderValue::from_static("Bearer")
+        } else {
+            HeaderValue::from_static("Basic realm=\"openspec-ui\", charset=\"UTF-8\"")
+        }
+    }
+}
+
+/// The auth settings in effect, swapped in place when the config file changes
+#[derive(Clone, Default)]
+pub struct AuthState(Arc<RwLock<Option<Arc<Auth>>>>);
+
+impl AuthState {
+    pub fn new(auth: Option<Auth>) -> Self {
+        Self(Arc::new(RwLock::new(auth.map(Arc::new))))
+    }
+
+    pub fn current(&self) -> Option<Arc<Auth>> {
+        self.0.read().unwrap().clone()
+    }
+
+    /// Apply auth settings read from the config file. Returns whether they differ
+    /// from the ones in effect; invalid settings are rejected and the current ones kept.
+    pub fn update(&self, config: Option<AuthConfig>) -> Result<bool, String> {
+        if self.current().map(|auth| auth.config.clone()) == config {
+            return Ok(false);
+        }
+        let auth = config.map(Auth::new).transpose()?;
+        *self.0.write().unwrap() = auth.map(Arc::new);
+        Ok(true)
+    }
+}
+
+#[derive(Serialize)]
+struct AuthErrorResponse {
+    error: String,
+}
+
+/// Router layer enforcing `Auth` on every route, including SSE and static assets
+pub async fn require_auth(State(auth): State<AuthState>, mut request: Request, next: Next) -> Response {
+    let Some(auth) = auth.current() else {
+        return next.run(request).await;
+    };
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let uri = request.uri().clone();
+    match auth.authorize(request.method(), uri.path(), uri.query(), request.headers(), peer).await {
+        Ok(principal) => {
+            if let Some(principal) = principal {
+                request.extensions_mut().insert(principal);
+            }
+            next.run(request).await
+        }
+        Err(error) => {
+            let status = match error {
+                AuthError::Forbidden(_) => StatusCode::FORBIDDEN,
+                _ => StatusCode::UNAUTHORIZED,
+            };
+            let mut response = (status, Json(AuthErrorResponse { error: error.to_string() })).into_response();
+            if status == StatusCode::UNAUTHORIZED {
+                response.headers_mut().insert(header::WWW_AUTHENTICATE, auth.challenge());
+            }
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth() -> Auth {
+        let password_hash = {
+            let salt = b"0123456789abcdef";
+            format!("{}$1000${}${}", HASH_PREFIX, STANDARD.encode(salt), STANDARD.encode(pbkdf2("s3cret", salt, 1000)))
+        };
+        Auth::new(AuthConfig {
+            tokens: vec![
+                TokenConfig {
+                    name: "ci".to_string(),
+                    token: "ci-token-0123456789".to_string(),
+                    scopes: vec![Scope::Read],
+                },
+                TokenConfig {
+                    name: "browser".to_string(),
+                    token: "a+b/c=d%e-0123456789".to_string(),
+                    scopes: vec![Scope::Read],
+                },
+            ],
+            users: vec![UserConfig {
+                username: "ada".to_string(),
+                password_hash,
+                scopes: vec![Scope::Read, Scope::WriteIdeas],
+            }],
+            proxy: Some(ProxyConfig {
+                user_header: default_user_header(),
+                scopes_header: Some("X-Forwarded-Scopes".to_string()),
+                scopes: default_proxy_scopes(),
+                trusted_proxies: default_trusted_proxies(),
+            }),
+        })
+        .unwrap()
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, value.parse().unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_required_scope() {
+        assert_eq!(required_scope(&Method::GET, "/api/health"), None);
+        assert_eq!(required_scope(&Method::OPTIONS, "/api/ideas"), None);
+        assert_eq!(required_scope(&Method::GET, "/assets/app.js"), Some(Scope::Read));
+        assert_eq!(required_scope(&Method::GET, "/api/events"), Some(Scope::Read));
+        assert_eq!(required_scope(&Method::DELETE, "/api/ideas/x%2Fy"), Some(Scope::WriteIdeas));
+        assert_eq!(required_scope(&Method::PUT, "/api/config/sources"), Some(Scope::AdminConfig));
+        assert_eq!(required_scope(&Method::PATCH, "/api/changes/x%2Fy/tasks/1"), Some(Scope::WriteChanges));
+    }
+
+    #[tokio::test]
+    async fn test_authorize() {
+        let auth = auth();
+        let local = Some(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let remote = Some("10.0.0.5".parse().unwrap());
+        async fn get(auth: &Auth, h: &HeaderMap, path: &str, query: Option<&str>, peer: Option<IpAddr>) -> Result<Option<Principal>, AuthError> {
+            auth.authorize(&Method::GET, path, query, h, peer).await
+        }
+
+        assert_eq!(get(&auth, &HeaderMap::new(), "/api/changes", None, remote).await, Err(AuthError::Unauthenticated));
+        assert_eq!(get(&auth, &HeaderMap::new(), "/api/health", None, remote).await, Ok(None));
+
+        let bearer = headers(&[("authorization", "Bearer ci-token-0123456789")]);
+        assert_eq!(get(&auth, &bearer, "/api/changes", None, remote).await.unwrap().unwrap().name, "ci");
+        assert_eq!(
+            auth.authorize(&Method::POST, "/api/ideas", None, &bearer, remote).await,
+            Err(AuthError::Forbidden(Scope::WriteIdeas))
+        );
+        let wrong = headers(&[("authorization", "Bearer nope")]);
+        assert_eq!(get(&auth, &wrong, "/api/changes", None, local).await, Err(AuthError::InvalidCredentials));
+
+        let basic = headers(&[("authorization", &format!("Basic {}", STANDARD.encode("ada:s3cret")))]);
+        for _ in 0..2 {
+            let principal = auth.authorize(&Method::POST, "/api/ideas", None, &basic, remote).await.unwrap().unwrap();
+            assert_eq!(principal.name, "ada");
+        }
+        let bad_password = headers(&[("authorization", &format!("Basic {}", STANDARD.encode("ada:guess")))]);
+        assert_eq!(get(&auth, &bad_password, "/", None, remote).await, Err(AuthError::InvalidCredentials));
+        let unknown_user = headers(&[("authorization", &format!("Basic {}", STANDARD.encode("grace:s3cret")))]);
+        assert_eq!(get(&auth, &unknown_user, "/", None, remote).await, Err(AuthError::InvalidCredentials));
+
+        let proxied = headers(&[("x-forwarded-user", "grace"), ("x-forwarded-scopes", "read, admin-config")]);
+        assert_eq!(
+            auth.authorize(&Method::PUT, "/api/config/sources", None, &proxied, local).await.unwrap().unwrap().name,
+            "grace"
+        );
+        assert_eq!(get(&auth, &proxied, "/", None, remote).await, Err(AuthError::Unauthenticated));
+
+        assert!(get(&auth, &HeaderMap::new(), "/api/events", Some("kind=change&access_token=ci-token-0123456789"), remote).await.is_ok());
+        // Query values arrive percent-encoded, as URLSearchParams writes them
+        let encoded = Some("access_token=a%2Bb%2Fc%3Dd%25e-0123456789");
+        assert_eq!(get(&auth, &HeaderMap::new(), "/api/events", encoded, remote).await.unwrap().unwrap().name, "browser");
+        assert_eq!(
+            get(&auth, &HeaderMap::new(), "/api/changes", Some("access_token=ci-token-0123456789"), remote).await,
+            Err(AuthError::Unauthenticated)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_auth_state_update() {
+        let config = auth().config;
+        let state = AuthState::new(Some(auth()));
+        assert_eq!(state.update(Some(config.clone())), Ok(false));
+
+        let mut rotated = config.clone();
+        rotated.tokens[0].token = "ci-token-rotated-0123".to_string();
+        assert_eq!(state.update(Some(rotated.clone())), Ok(true));
+        let bearer = headers(&[("authorization", "Bearer ci-token-0123456789")]);
+        let current = state.current().unwrap();
+        assert_eq!(current.authenticate(&bearer, "/", None, None).await, Err(AuthError::InvalidCredentials));
+
+        let mut invalid = rotated.clone();
+        invalid.tokens[0].token = "short".to_string();
+        assert!(state.update(Some(invalid)).is_err());
+        assert_eq!(state.current().unwrap().config, rotated);
+
+        assert_eq!(state.update(None), Ok(true));
+        assert!(state.current().is_none());
+    }
+
+    #[test]
+    fn test_password_hash_format() {
+        let hash = hash_password_with_rounds("pw", 1000).unwrap();
+        let parsed = parse_password_hash(&hash).unwrap();
+        assert_eq!(parsed.rounds, 1000);
+        assert_eq!(pbkdf2("pw", &parsed.salt, 1000).to_vec(), parsed.hash);
+        assert_eq!(parsed.salt.len(), 16);
+        assert!(parse_password_hash("plain").is_none());
+        assert!(Auth::new(AuthConfig {
+            users: vec![UserConfig { username: "x".to_string(), password_hash: "plain".to_string(), scopes: vec![] }],
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,515 @@
+use crate::auth;
+use crate::config::{Config, Source};
+use crate::export;
+use crate::parser::{self, ChangeStatus};
+use crate::paths;
+use crate::report::{self, ReportFormat, ReportRange};
+use crate::validate::{self, Severity, SourceDiagnostics};
+use clap::{Args, Subcommand, ValueEnum};
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Human,
+    Json,
+}
+
+#[derive(Args, Debug)]
+pub struct ValidateArgs {
+    /// OpenSpec directories to check instead of the configured sources
+    pub paths: Vec<PathBuf>,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    pub format: OutputFormat,
+    /// Treat warnings as errors
+    #[arg(long)]
+    pub strict: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ListKind {
+    Changes,
+    Specs,
+    Ideas,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub enum StatusFilter {
+    Draft,
+    Todo,
+    InProgress,
+    Done,
+    Archived,
+}
+
+impl StatusFilter {
+    fn matches(self, status: ChangeStatus) -> bool {
+        status
+            == match self {
+                StatusFilter::Draft => ChangeStatus::Draft,
+                StatusFilter::Todo => ChangeStatus::Todo,
+                StatusFilter::InProgress => ChangeStatus::InProgress,
+                StatusFilter::Done => ChangeStatus::Done,
+                StatusFilter::Archived => ChangeStatus::Archived,
+            }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct ListArgs {
+    pub kind: ListKind,
+    /// Only include this source
+    #[arg(long)]
+    pub source: Option<String>,
+    /// Only include changes with this status
+    #[arg(long, value_enum)]
+    pub status: Option<StatusFilter>,
+    /// Print JSON instead of a table
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ShowArgs {
+    /// Change, spec or idea id, e.g. `my-project/add-login`
+    pub id: String,
+    /// Print JSON instead of text
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    /// Directory to write the site into; created if missing
+    #[arg(long)]
+    pub out: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct ReportArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ReportFormat::Md)]
+    pub format: ReportFormat,
+    /// First day (YYYY-MM-DD) for archived changes and new ideas; defaults to a week before --to
+    #[arg(long)]
+    pub from: Option<String>,
+    /// Last day (YYYY-MM-DD); defaults to today
+    #[arg(long)]
+    pub to: Option<String>,
+    /// Only include this source
+    #[arg(long)]
+    pub source: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Parse the config file and check its sources and auth settings; exits 1 on errors
+    Check,
+    /// Print the JSON Schema of the config file, for editor completion
+    Schema,
+}
+
+/// Exit codes shared by the subcommands
+pub const EXIT_OK: i32 = 0;
+pub const EXIT_FAILED: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+
+/// Write command output to stdout, ignoring a closed pipe (e.g. `| head`)
+pub fn emit(output: &str) {
+    let _ = std::io::stdout().lock().write_all(output.as_bytes());
+}
+
+/// Sources named on the command line, identified by their directory name
+pub fn sources_from_paths(paths: &[PathBuf]) -> Vec<Source> {
+    paths
+        .iter()
+        .map(|path| {
+            let name = path
+                .canonicalize()
+                .ok()
+                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+                .unwrap_or_else(|| path.display().to_string());
+            Source {
+                id: name.clone(),
+                name,
+                path: path.clone(),
+                valid: path.is_dir(),
+                writable: false,
+            }
+        })
+        .collect()
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ValidateReport {
+    errors: usize,
+    warnings: usize,
+    sources: Vec<SourceDiagnostics>,
+}
+
+/// Lint every source and print the diagnostics; returns the process exit code
+pub fn validate(sources: &[Source], args: &ValidateArgs) -> i32 {
+    if let Some(invalid) = sources.iter().find(|s| !s.valid) {
+        eprintln!("error: {} is not a directory", invalid.path.display());
+        return EXIT_USAGE;
+    }
+
+    let reports: Vec<SourceDiagnostics> = sources
+        .iter()
+        .map(|s| validate::check_source(&s.path, &s.id))
+        .collect();
+    let report = ValidateReport {
+        errors: reports.iter().map(|r| r.errors).sum(),
+        warnings: reports.iter().map(|r| r.warnings).sum(),
+        sources: reports,
+    };
+
+    let mut out = String::new();
+    match args.format {
+        OutputFormat::Json => match serde_json::to_string_pretty(&report) {
+            Ok(json) => out = json + "\n",
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return EXIT_FAILED;
+            }
+        },
+        OutputFormat::Human => {
+            for (source, diagnostics) in sources.iter().zip(&report.sources) {
+                for d in diagnostics.changes.iter().chain(&diagnostics.specs).flat_map(|e| &e.diagnostics) {
+                    let location = match d.line {
+                        Some(line) => format!("{}:{}", source.path.join(&d.file).display(), line),
+                        None => source.path.join(&d.file).display().to_string(),
+                    };
+                    let severity = match d.severity {
+                        Severity::Error => "error",
+                        Severity::Warning => "warning",
+                    };
+                    let _ = writeln!(out, "{}: {}[{}] {}", location, severity, d.rule, d.message);
+                }
+            }
+            let _ = writeln!(
+                out,
+                "{} error(s), {} warning(s) in {} source(s)",
+                report.errors,
+                report.warnings,
+                sources.len()
+            );
+        }
+    }
+    emit(&out);
+
+    if report.errors > 0 || (args.strict && report.warnings > 0) {
+        EXIT_FAILED
+    } else {
+        EXIT_OK
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, i32> {
+    serde_json::to_string_pretty(value).map(|json| json + "\n").map_err(|e| {
+        eprintln!("error: {}", e);
+        EXIT_FAILED
+    })
+}
+
+/// snake_case name of a status, as used in JSON and `--status`
+fn status_name(status: ChangeStatus) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Render rows as space-aligned columns under a header
+fn table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.len()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let header: Vec<String> = header.iter().map(|h| h.to_string()).collect();
+    for row in std::iter::once(&header).chain(rows) {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        let _ = writeln!(out, "{}", cells.join("  ").trim_end());
+    }
+    out
+}
+
+fn run(result: Result<String, i32>) -> i32 {
+    match result {
+        Ok(out) => {
+            emit(&out);
+            EXIT_OK
+        }
+        Err(code) => code,
+    }
+}
+
+/// List changes, specs or ideas across sources; returns the process exit code
+pub fn list(sources: &[Source], args: &ListArgs) -> i32 {
+    if let Some(id) = &args.source {
+        if !sources.iter().any(|s| &s.id == id) {
+            eprintln!("error: unknown source '{}'", id);
+            return EXIT_USAGE;
+        }
+    }
+    let sources: Vec<&Source> = sources
+        .iter()
+        .filter(|s| s.valid && args.source.as_ref().is_none_or(|id| &s.id == id))
+        .collect();
+
+    run(match args.kind {
+        ListKind::Changes => {
+            let changes: Vec<_> = sources
+                .iter()
+                .flat_map(|s| parser::scan_changes(&s.path, &s.id))
+                .filter(|c| args.status.is_none_or(|status| status.matches(c.status)))
+                .collect();
+            if args.json {
+                to_json(&changes)
+            } else {
+                let rows: Vec<Vec<String>> = changes
+                    .iter()
+                    .map(|c| {
+                        let tasks = c.task_stats.as_ref().map_or("-".to_string(), |t| format!("{}/{}", t.done, t.total));
+                        vec![c.id.clone(), status_name(c.status), tasks]
+                    })
+                    .collect();
+                Ok(table(&["ID", "STATUS", "TASKS"], &rows))
+            }
+        }
+        ListKind::Specs => {
+            let specs: Vec<_> = sources.iter().flat_map(|s| parser::scan_specs(&s.path, &s.id)).collect();
+            if args.json {
+                to_json(&specs)
+            } else {
+                let rows: Vec<Vec<String>> = specs.iter().map(|s| vec![s.id.clone(), s.path.clone()]).collect();
+                Ok(table(&["ID", "PATH"], &rows))
+            }
+        }
+        ListKind::Ideas => {
+            let ideas: Vec<_> = sources.iter().flat_map(|s| parser::scan_ideas(&s.path, &s.id)).collect();
+            if args.json {
+                to_json(&ideas)
+            } else {
+                let rows: Vec<Vec<String>> = ideas
+                    .iter()
+                    .map(|i| vec![i.id.clone(), i.title.clone(), i.created_at.clone()])
+                    .collect();
+                Ok(table(&["ID", "TITLE", "CREATED"], &rows))
+            }
+        }
+    })
+}
+
+/// Print a change, spec or idea by id, trying them in that order
+pub fn show(sources: &[Source], args: &ShowArgs) -> i32 {
+    let Some((source_id, name)) = args.id.split_once('/') else {
+        eprintln!("error: id must look like <source>/<name>");
+        return EXIT_USAGE;
+    };
+    let Some(source) = sources.iter().find(|s| s.id == source_id && s.valid) else {
+        eprintln!("error: unknown source '{}'", source_id);
+        return EXIT_USAGE;
+    };
+
+    if let Some(change) = parser::get_change_detail(&source.path, &source.id, name) {
+        return run(if args.json {
+            to_json(&change)
+        } else {
+            let mut out = format!("{} ({})\n", change.id, status_name(change.status));
+            if let Some(tasks) = &change.tasks {
+                let _ = writeln!(out, "Tasks: {}/{} done", tasks.stats.done, tasks.stats.total);
+            }
+            for spec in &change.specs {
+                let _ = writeln!(out, "Delta: specs/{}", spec.path);
+            }
+            for (label, text) in [("proposal.md", &change.proposal), ("design.md", &change.design)] {
+                if let Some(text) = text {
+                    let _ = write!(out, "\n--- {} ---\n{}\n", label, text.trim_end());
+                }
+            }
+            Ok(out)
+        });
+    }
+
+    let spec = [format!("{}/spec.md", name), format!("{}.md", name), name.to_string()]
+        .iter()
+        .find_map(|path| parser::get_spec_detail(&source.path, &source.id, path));
+    if let Some(spec) = spec {
+        return run(if args.json {
+            to_json(&spec)
+        } else {
+            Ok(format!("{} ({})\n\n{}\n", spec.id, spec.path, spec.content.trim_end()))
+        });
+    }
+
+    let idea = paths::resolve_entry(&source.path, "ideas", name, ".md")
+        .ok()
+        .and_then(|idea_path| parser::read_idea(&idea_path, &source.id));
+    if let Some(idea) = idea {
+        return run(if args.json {
+            to_json(&idea)
+        } else {
+            Ok(format!("{}\n{}\n\n{}\n", idea.id, idea.title, idea.description.trim_end()))
+        });
+    }
+
+    eprintln!("error: no change, spec or idea with id '{}'", args.id);
+    EXIT_FAILED
+}
+
+/// Write the static site for every configured source; returns the process exit code
+pub fn export_site(sources: &[Source], args: &ExportArgs) -> i32 {
+    match export::export_site(sources, &args.out) {
+        Ok(summary) => {
+            emit(&format!(
+                "Exported {} change(s), {} spec(s) and {} idea(s) to {}\n",
+                summary.changes,
+                summary.specs,
+                summary.ideas,
+                args.out.display()
+            ));
+            EXIT_OK
+        }
+        Err(e) => {
+            eprintln!("error: failed to write {}: {}", args.out.display(), e);
+            EXIT_FAILED
+        }
+    }
+}
+
+/// Print a status report for the configured sources; returns the process exit code
+pub fn report(sources: &[Source], args: &ReportArgs) -> i32 {
+    let today = chrono::Local::now().date_naive();
+    let range = match ReportRange::parse(args.from.as_deref(), args.to.as_deref(), today) {
+        Ok(range) => range,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return EXIT_USAGE;
+        }
+    };
+    if let Some(id) = &args.source {
+        if !sources.iter().any(|s| &s.id == id) {
+            eprintln!("error: unknown source '{}'", id);
+            return EXIT_USAGE;
+        }
+    }
+    let sources: Vec<Source> = sources
+        .iter()
+        .filter(|s| s.valid && args.source.as_ref().is_none_or(|id| &s.id == id))
+        .cloned()
+        .collect();
+
+    let changes: Vec<_> = sources.iter().flat_map(|s| parser::scan_changes(&s.path, &s.id)).collect();
+    let ideas: Vec<_> = sources.iter().flat_map(|s| parser::scan_ideas(&s.path, &s.id)).collect();
+    let report = report::build(&sources, &changes, &ideas, range);
+
+    run(match args.format {
+        ReportFormat::Md => Ok(report::to_markdown(&report)),
+        ReportFormat::Csv => Ok(report::to_csv(&report)),
+        ReportFormat::Json => to_json(&report),
+    })
+}
+
+/// Read a password from stdin and print its hash for `auth.users[].passwordHash`
+pub fn hash_password() -> i32 {
+    let mut password = String::new();
+    if let Err(e) = std::io::stdin().read_line(&mut password) {
+        eprintln!("error: {}", e);
+        return EXIT_FAILED;
+    }
+    let password = password.trim_end_matches(['\r', '\n']);
+    if password.is_empty() {
+        eprintln!("error: no password on stdin");
+        return EXIT_USAGE;
+    }
+    match auth::hash_password(password) {
+        Ok(hash) => {
+            emit(&format!("{}\n", hash));
+            EXIT_OK
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            EXIT_FAILED
+        }
+    }
+}
+
+/// Run a `config` subcommand against the config file at `path`
+pub fn config(path: &Path, command: &ConfigCommand) -> i32 {
+    match command {
+        ConfigCommand::Check => config_check(path),
+        ConfigCommand::Schema => match to_json(&Config::json_schema()) {
+            Ok(json) => {
+                emit(&json);
+                EXIT_OK
+            }
+            Err(code) => code,
+        },
+    }
+}
+
+fn config_check(path: &Path) -> i32 {
+    if !path.exists() {
+        eprintln!("error: config file not found: {}", path.display());
+        return EXIT_USAGE;
+    }
+    let config = match Config::load(path) {
+        Ok(config) => config,
+        Err(e) => {
+            emit(&format!("{}: error: {}\n", path.display(), e));
+            return EXIT_FAILED;
+        }
+    };
+
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    let default_path = PathBuf::from(".");
+    for source in config.resolve_sources(path.parent().unwrap_or(&default_path)) {
+        if !source.valid {
+            warnings.push(format!(
+                "source '{}': {} is not a directory and will be skipped",
+                source.name,
+                source.path.display()
+            ));
+        }
+    }
+    if let Some(Err(e)) = config.auth.map(auth::Auth::new) {
+        errors.push(format!("auth: {}", e));
+    }
+
+    let mut out = String::new();
+    for error in &errors {
+        let _ = writeln!(out, "{}: error: {}", path.display(), error);
+    }
+    for warning in &warnings {
+        let _ = writeln!(out, "{}: warning: {}", path.display(), warning);
+    }
+    let _ = writeln!(
+        out,
+        "{} error(s), {} warning(s), {} source(s)",
+        errors.len(),
+        warnings.len(),
+        config.sources.len()
+    );
+    emit(&out);
+
+    if errors.is_empty() {
+        EXIT_OK
+    } else {
+        EXIT_FAILED
+    }
+}
@@ -1,47 +1,175 @@
+use crate::auth::AuthConfig;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
+/// Config file names looked up in the working directory when none is given
+const DEFAULT_CONFIG_FILES: [&str; 4] = ["openspec-ui.json", "openspec-ui.yaml", "openspec-ui.yml", "openspec-ui.toml"];
+
 #[derive(Debug, Error)]
 pub enum ConfigError {
     #[error("Failed to read config file: {0}")]
-    ReadError(#[from] std::io::Error),
+    Read(#[from] std::io::Error),
     #[error("Failed to parse config file: {0}")]
-    ParseError(#[from] serde_json::Error),
+    Parse(String),
+    #[error("Failed to write config file: {0}")]
+    Write(String),
+    #[error("{0} config files are edited by hand, so the dashboard does not rewrite them and drop their comments; edit the file instead")]
+    HandEdited(&'static str),
+    #[error("Source name '{0}' is used more than once")]
+    DuplicateSource(String),
+}
+
+/// Config file syntax, chosen by file extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl ConfigFormat {
+    /// `.yaml`/`.yml` and `.toml` files; anything else is read as JSON
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+            Some("yaml" | "yml") => ConfigFormat::Yaml,
+            Some("toml") => ConfigFormat::Toml,
+            _ => ConfigFormat::Json,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ConfigFormat::Json => "JSON",
+            ConfigFormat::Yaml => "YAML",
+            ConfigFormat::Toml => "TOML",
+        }
+    }
+}
+
+/// The first source name that appears twice; names double as source ids, so they must be unique
+pub fn duplicate_source_name(sources: &[SourceConfig]) -> Option<&str> {
+    let mut names = HashSet::new();
+    sources.iter().map(|s| s.name.as_str()).find(|name| !names.insert(*name))
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// The first existing default config file, or `openspec-ui.json`
+pub fn default_config_path() -> PathBuf {
+    DEFAULT_CONFIG_FILES
+        .iter()
+        .map(PathBuf::from)
+        .find(|path| path.exists())
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_FILES[0]))
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct SourceConfig {
+    /// Display name, also used as the source id
     pub name: String,
+    /// Path to the `openspec/` directory; `./` and `../` paths are relative to the config file
     pub path: String,
+    /// Allow the dashboard to write ideas and changes into this source
+    #[serde(default = "default_writable")]
+    pub writable: bool,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+fn default_writable() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
+    /// Schema reference for editor completion, e.g. `./openspec-ui.schema.json`
+    #[serde(rename = "$schema", default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    /// OpenSpec directories to monitor
     pub sources: Vec<SourceConfig>,
+    /// Server port; the `PORT` environment variable takes precedence
     #[serde(default = "default_port")]
     pub port: u16,
+    /// Reject every write, including source list changes
+    #[serde(default)]
+    pub read_only: bool,
+    /// When absent, the server is open to anyone who can reach it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<AuthConfig>,
 }
 
 fn default_port() -> u16 {
     3000
 }
 
-#[derive(Debug, Clone)]
+/// 1-based line and column of a byte offset
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Source {
     pub id: String,
     pub name: String,
     pub path: PathBuf,
     pub valid: bool,
+    /// False when the source or the whole server is read-only
+    pub writable: bool,
 }
 
 impl Config {
+    /// Read a config file in the format given by its extension
     pub fn load(path: &Path) -> Result<Self, ConfigError> {
         let content = std::fs::read_to_string(path)?;
-        let config: Config = serde_json::from_str(&content)?;
+        Self::parse(&content, ConfigFormat::from_path(path))
+    }
+
+    /// Parse config text. Unknown keys are rejected and errors carry the line and column;
+    /// duplicate source names are rejected too.
+    pub fn parse(content: &str, format: ConfigFormat) -> Result<Self, ConfigError> {
+        let config: Self = match format {
+            ConfigFormat::Json => serde_json::from_str(content).map_err(|e| ConfigError::Parse(e.to_string())),
+            ConfigFormat::Yaml => serde_yaml::from_str(content).map_err(|e| ConfigError::Parse(e.to_string())),
+            ConfigFormat::Toml => toml::from_str(content).map_err(|e| {
+                // toml renders a multi-line snippet; keep errors on one line like the other formats
+                let message = e.message().trim_end();
+                ConfigError::Parse(match e.span() {
+                    Some(span) => {
+                        let (line, column) = line_column(content, span.start);
+                        format!("{} at line {} column {}", message, line, column)
+                    }
+                    None => message.to_string(),
+                })
+            }),
+        }?;
+        if let Some(name) = duplicate_source_name(&config.sources) {
+            return Err(ConfigError::DuplicateSource(name.to_string()));
+        }
         Ok(config)
     }
 
+    /// Write the config back as JSON. YAML and TOML files are refused: re-serializing
+    /// them would drop the comments and formatting they were written with.
+    pub fn save(&self, path: &Path) -> Result<(), ConfigError> {
+        let format = ConfigFormat::from_path(path);
+        if format != ConfigFormat::Json {
+            return Err(ConfigError::HandEdited(format.name()));
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map(|json| json + "\n")
+            .map_err(|e| ConfigError::Write(e.to_string()))?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// JSON Schema of the config file, as published in `openspec-ui.schema.json`
+    pub fn json_schema() -> serde_json::Value {
+        schemars::schema_for!(Config).to_value()
+    }
+
     pub fn resolve_sources(&self, base_path: &Path) -> Vec<Source> {
         self.sources
             .iter()
@@ -60,8 +188,73 @@ impl Config {
                     name: s.name.clone(),
                     path,
                     valid,
+                    writable: s.writable && !self.read_only,
                 }
             })
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_parse_formats() {
+        let json = r#"{"$schema": "./openspec-ui.schema.json", "sources": [{"name": "a", "path": "./openspec"}]}"#;
+        let yaml = "sources:\n  - name: a\n    path: ./openspec\nread_only: true\n";
+        let toml = "port = 4000\n\n[[sources]]\nname = \"a\"\npath = \"./openspec\"\nwritable = false\n";
+
+        let config = Config::parse(json, ConfigFormat::Json).unwrap();
+        assert_eq!((config.sources[0].name.as_str(), config.port), ("a", 3000));
+        assert!(Config::parse(yaml, ConfigFormat::Yaml).unwrap().read_only);
+        let config = Config::parse(toml, ConfigFormat::Toml).unwrap();
+        assert_eq!(config.port, 4000);
+        assert!(!config.sources[0].writable);
+
+        assert_eq!(ConfigFormat::from_path(Path::new("openspec-ui.YML")), ConfigFormat::Yaml);
+        assert_eq!(ConfigFormat::from_path(Path::new("openspec-ui.toml")), ConfigFormat::Toml);
+        assert_eq!(ConfigFormat::from_path(Path::new("openspec-ui")), ConfigFormat::Json);
+    }
+
+    #[test]
+    fn test_unknown_fields_are_rejected_with_location() {
+        let error = |content: &str, format| Config::parse(content, format).unwrap_err().to_string();
+
+        let json = error("{\n  \"sources\": [],\n  \"prot\": 3000\n}", ConfigFormat::Json);
+        assert!(json.contains("unknown field `prot`") && json.contains("line 3 column"), "{}", json);
+        let yaml = error("sources:\n  - name: a\n    pth: ./openspec\n", ConfigFormat::Yaml);
+        assert!(yaml.contains("unknown field `pth`") && yaml.contains("line 3 column 5"), "{}", yaml);
+        let toml = error("sources = []\n\n[auth]\ntoken = \"x\"\n", ConfigFormat::Toml);
+        assert!(toml.contains("unknown field `token`") && toml.contains("line 4 column 1"), "{}", toml);
+        assert!(!toml.contains('\n'));
+
+        let duplicate = error("sources:\n  - name: a\n    path: ./one\n  - name: a\n    path: ./two\n", ConfigFormat::Yaml);
+        assert_eq!(duplicate, "Source name 'a' is used more than once");
+    }
+
+    #[test]
+    fn test_save_refuses_hand_edited_formats() {
+        let dir = TempDir::new("config-save");
+        let yaml = "# my sources\nsources:\n  - name: a\n    path: ./openspec\n";
+        std::fs::write(dir.join("openspec-ui.yaml"), yaml).unwrap();
+
+        let config = Config::load(&dir.join("openspec-ui.yaml")).unwrap();
+        assert!(matches!(config.save(&dir.join("openspec-ui.yaml")), Err(ConfigError::HandEdited("YAML"))));
+        assert_eq!(std::fs::read_to_string(dir.join("openspec-ui.yaml")).unwrap(), yaml);
+        config.save(&dir.join("openspec-ui.json")).unwrap();
+        assert_eq!(Config::load(&dir.join("openspec-ui.json")).unwrap().sources[0].name, "a");
+    }
+
+    #[test]
+    fn test_published_schema_is_current() {
+        let published = include_str!("../../openspec-ui.schema.json");
+        let published: serde_json::Value = serde_json::from_str(published).unwrap();
+        assert_eq!(
+            published,
+            Config::json_schema(),
+            "regenerate with `openspec-ui config schema > openspec-ui.schema.json`"
+        );
+    }
+}
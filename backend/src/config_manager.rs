@@ -1,47 +1,173 @@
-use crate::config::{Config, Source, SourceConfig};
+use crate::auth::{Auth, AuthConfig, AuthState};
+use crate::config::{Config, ConfigFormat, Source, SourceConfig};
+use crate::events::{EventBus, UpdateEvent};
+use crate::history::HistoryCache;
+use crate::index::Index;
 use serde::Serialize;
 use std::{
-    path::PathBuf,
-    sync::Arc,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
 };
-use tokio::sync::{broadcast, RwLock};
+use tokio::sync::{broadcast, OwnedMutexGuard, RwLock};
 
 #[derive(Clone)]
 pub struct AppState {
     pub inner: Arc<RwLock<AppStateInner>>,
     pub config_manager: Arc<ConfigManager>,
-    pub update_tx: broadcast::Sender<()>,
+    pub events: Arc<EventBus>,
+    pub index: Arc<RwLock<Index>>,
+    pub history: Arc<HistoryCache>,
+    /// Read by the auth middleware on every request
+    pub auth: AuthState,
+    /// Serializes index rebuilds and refreshes, which scan the disk without holding `index`
+    index_update: Arc<tokio::sync::Mutex<()>>,
+    file_locks: Arc<Mutex<HashMap<PathBuf, Arc<tokio::sync::Mutex<()>>>>>,
 }
 
 pub struct AppStateInner {
     pub sources: Vec<Source>,
+    pub read_only: bool,
+    /// Why the config file on disk was last rejected; the previous config stays active
+    pub config_error: Option<String>,
     pub config_update_tx: broadcast::Sender<()>,
 }
 
 impl AppState {
     pub fn new(
         sources: Vec<Source>,
+        read_only: bool,
+        auth: Option<Auth>,
         config_manager: Arc<ConfigManager>,
-        update_tx: broadcast::Sender<()>,
         config_update_tx: broadcast::Sender<()>,
     ) -> Self {
+        let index = Index::build(&sources);
         Self {
             inner: Arc::new(RwLock::new(AppStateInner {
                 sources,
+                read_only,
+                config_error: None,
                 config_update_tx,
             })),
             config_manager,
-            update_tx,
+            events: Arc::new(EventBus::new()),
+            index: Arc::new(RwLock::new(index)),
+            history: Arc::new(HistoryCache::default()),
+            auth: AuthState::new(auth),
+            index_update: Arc::default(),
+            file_locks: Arc::default(),
         }
     }
 
+    /// Serialize read-check-write cycles on one file across concurrent requests.
+    /// The lock is released when the returned guard is dropped.
+    pub async fn lock_file(&self, path: &Path) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.file_locks.lock().unwrap();
+            // Forget locks nobody holds or waits for
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+            locks.entry(path.to_path_buf()).or_default().clone()
+        };
+        lock.lock_owned().await
+    }
+
+    /// Run `f` against the git history cache on the blocking pool, since a cache
+    /// miss walks the whole repository
+    pub async fn with_history<T, F>(&self, f: F) -> T
+    where
+        F: FnOnce(&HistoryCache) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let history = self.history.clone();
+        tokio::task::spawn_blocking(move || f(&history))
+            .await
+            .expect("history task panicked")
+    }
+
     pub async fn get_sources(&self) -> Vec<Source> {
         self.inner.read().await.sources.clone()
     }
 
+    pub async fn read_only(&self) -> bool {
+        self.inner.read().await.read_only
+    }
+
+    pub async fn config_error(&self) -> Option<String> {
+        self.inner.read().await.config_error.clone()
+    }
+
+    /// Re-read the config file and apply it. An invalid file keeps the current
+    /// config and records the error; otherwise auth settings take effect right away
+    /// and, if the sources or the read-only flag changed, the index is rebuilt and
+    /// the file watchers are told to restart.
+    /// Returns the new sources when they or the read-only flag changed.
+    pub async fn reload_config(&self) -> Result<Option<Vec<Source>>, String> {
+        let loaded = self
+            .config_manager
+            .load_state()
+            .map_err(|e| e.to_string())
+            .and_then(|(sources, read_only, auth)| {
+                let auth_changed = self.auth.update(auth).map_err(|e| format!("Invalid auth config: {}", e))?;
+                Ok((sources, read_only, auth_changed))
+            });
+        let (sources, read_only) = match loaded {
+            Ok((sources, read_only, auth_changed)) => {
+                if auth_changed {
+                    tracing::info!("Reloaded auth settings");
+                }
+                (sources, read_only)
+            }
+            Err(error) => {
+                self.inner.write().await.config_error = Some(error.clone());
+                return Err(error);
+            }
+        };
+
+        {
+            let mut inner = self.inner.write().await;
+            inner.config_error = None;
+            if inner.sources == sources && inner.read_only == read_only {
+                return Ok(None);
+            }
+            inner.read_only = read_only;
+        }
+
+        self.update_sources(sources.clone()).await;
+        let _ = self.inner.read().await.config_update_tx.send(());
+        Ok(Some(sources))
+    }
+
+    /// Switch to a new source list, rebuilding the index on the blocking pool
+    /// so requests keep being served from the current one meanwhile
     pub async fn update_sources(&self, sources: Vec<Source>) {
+        let _updating = self.index_update.lock().await;
+        let scanned = sources.clone();
+        let index = tokio::task::spawn_blocking(move || Index::build(&scanned))
+            .await
+            .expect("index build panicked");
         let mut inner = self.inner.write().await;
         inner.sources = sources;
+        *self.index.write().await = index;
+    }
+
+    /// Re-read the index entries for paths that changed on disk and
+    /// broadcast the resulting events to SSE clients.
+    /// The disk is read from a snapshot, so the index is only locked to swap the result in.
+    pub async fn refresh_paths(&self, paths: &[PathBuf]) {
+        let _updating = self.index_update.lock().await;
+        let snapshot = self.index.read().await.snapshot();
+        let paths = paths.to_vec();
+        let update = tokio::task::spawn_blocking(move || snapshot.refresh(&paths))
+            .await
+            .expect("index refresh panicked");
+        let events = self.index.write().await.install(update);
+        for event in events {
+            self.events.publish(event);
+        }
+    }
+
+    pub fn publish(&self, event: UpdateEvent) {
+        self.events.publish(event);
     }
 
     pub async fn config_manager(&self) -> Arc<ConfigManager> {
@@ -65,10 +191,23 @@ impl ConfigManager {
     }
 
     pub fn load_sources(&self) -> Result<Vec<Source>, anyhow::Error> {
+        Ok(self.load_state()?.0)
+    }
+
+    /// Sources, the read-only flag and auth settings from a single read of the config file
+    pub fn load_state(&self) -> Result<(Vec<Source>, bool, Option<AuthConfig>), anyhow::Error> {
         let config = Config::load(&self.config_path)?;
         let default_path = PathBuf::from(".");
         let base_path = self.config_path.parent().unwrap_or(&default_path);
-        Ok(config.resolve_sources(base_path))
+        Ok((config.resolve_sources(base_path), config.read_only, config.auth))
+    }
+
+    pub fn load_read_only(&self) -> Result<bool, anyhow::Error> {
+        Ok(Config::load(&self.config_path)?.read_only)
+    }
+
+    pub fn load_auth(&self) -> Result<Option<AuthConfig>, anyhow::Error> {
+        Ok(Config::load(&self.config_path)?.auth)
     }
 
     pub fn get_config_response(&self) -> Result<ConfigResponse, anyhow::Error> {
@@ -109,13 +248,16 @@ impl ConfigManager {
         (valid, warnings)
     }
 
+    /// Format of the config file, which decides whether the dashboard may rewrite it
+    pub fn format(&self) -> ConfigFormat {
+        ConfigFormat::from_path(&self.config_path)
+    }
+
     pub fn save_sources(&self, sources: &[SourceConfig]) -> Result<(), anyhow::Error> {
         // Load existing config to preserve other fields (like port)
         let mut config = Config::load(&self.config_path)?;
         config.sources = sources.to_vec();
-
-        let content = serde_json::to_string_pretty(&config)?;
-        std::fs::write(&self.config_path, content)?;
+        config.save(&self.config_path)?;
         Ok(())
     }
 }
@@ -0,0 +1,343 @@
+use crate::parser::{Change, Idea, Spec};
+use serde::Serialize;
+use std::{collections::VecDeque, sync::Mutex};
+use tokio::sync::broadcast;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    ChangeCreated,
+    ChangeUpdated,
+    ChangeArchived,
+    ChangeDeleted,
+    SpecCreated,
+    SpecUpdated,
+    SpecDeleted,
+    IdeaCreated,
+    IdeaUpdated,
+    IdeaDeleted,
+    SourcesReloaded,
+}
+
+impl EventKind {
+    /// SSE event name, e.g. `change.updated`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::ChangeCreated => "change.created",
+            EventKind::ChangeUpdated => "change.updated",
+            EventKind::ChangeArchived => "change.archived",
+            EventKind::ChangeDeleted => "change.deleted",
+            EventKind::SpecCreated => "spec.created",
+            EventKind::SpecUpdated => "spec.updated",
+            EventKind::SpecDeleted => "spec.deleted",
+            EventKind::IdeaCreated => "idea.created",
+            EventKind::IdeaUpdated => "idea.updated",
+            EventKind::IdeaDeleted => "idea.deleted",
+            EventKind::SourcesReloaded => "sources.reloaded",
+        }
+    }
+}
+
+/// A typed update pushed to SSE clients.
+/// `data` carries the new summary object and is null for deletions.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateEvent {
+    #[serde(skip)]
+    pub kind: EventKind,
+    pub id: String,
+    pub source_id: Option<String>,
+    /// Id of the active change a `change.archived` event replaces, if it was seen
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_id: Option<String>,
+    pub data: Option<serde_json::Value>,
+}
+
+impl UpdateEvent {
+    fn new<T: Serialize>(kind: EventKind, id: &str, source_id: &str, data: Option<&T>) -> Self {
+        Self {
+            kind,
+            id: id.to_string(),
+            source_id: Some(source_id.to_string()),
+            previous_id: None,
+            data: data.and_then(|d| serde_json::to_value(d).ok()),
+        }
+    }
+
+    pub fn change(kind: EventKind, change: &Change) -> Self {
+        Self::new(kind, &change.id, &change.source_id, Some(change))
+    }
+
+    pub fn change_deleted(id: &str, source_id: &str) -> Self {
+        Self::new::<Change>(EventKind::ChangeDeleted, id, source_id, None)
+    }
+
+    pub fn spec(kind: EventKind, spec: &Spec) -> Self {
+        Self::new(kind, &spec.id, &spec.source_id, Some(spec))
+    }
+
+    pub fn spec_deleted(id: &str, source_id: &str) -> Self {
+        Self::new::<Spec>(EventKind::SpecDeleted, id, source_id, None)
+    }
+
+    pub fn idea(kind: EventKind, idea: &Idea) -> Self {
+        Self::new(kind, &idea.id, &idea.source_id, Some(idea))
+    }
+
+    pub fn idea_deleted(id: &str, source_id: &str) -> Self {
+        Self::new::<Idea>(EventKind::IdeaDeleted, id, source_id, None)
+    }
+
+    pub fn sources_reloaded<T: Serialize>(sources: &T) -> Self {
+        Self {
+            kind: EventKind::SourcesReloaded,
+            id: "sources".to_string(),
+            source_id: None,
+            previous_id: None,
+            data: serde_json::to_value(sources).ok(),
+        }
+    }
+}
+
+/// Subscription filter from `/api/events?source=a,b&kind=change,idea.created`.
+/// A kind matches either a category (`change`) or a full event name (`change.updated`).
+/// Events without a source, such as `sources.reloaded`, pass any source filter.
+#[derive(Debug, Default)]
+pub struct EventFilter {
+    sources: Option<Vec<String>>,
+    kinds: Option<Vec<String>>,
+}
+
+fn split_list(value: Option<&str>) -> Option<Vec<String>> {
+    let items: Vec<String> = value?
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    (!items.is_empty()).then_some(items)
+}
+
+impl EventFilter {
+    pub fn new(sources: Option<&str>, kinds: Option<&str>) -> Self {
+        Self {
+            sources: split_list(sources),
+            kinds: split_list(kinds),
+        }
+    }
+
+    pub fn matches(&self, event: &UpdateEvent) -> bool {
+        let source_ok = match (&self.sources, &event.source_id) {
+            (Some(sources), Some(source_id)) => sources.contains(source_id),
+            _ => true,
+        };
+        let kind_ok = self.kinds.as_ref().is_none_or(|kinds| {
+            let name = event.kind.as_str();
+            let category = name.split('.').next().unwrap_or(name);
+            kinds.iter().any(|k| k == name || k == category)
+        });
+        source_ok && kind_ok
+    }
+}
+
+/// Number of recent events kept for clients resuming with `Last-Event-ID`
+const REPLAY_CAPACITY: usize = 1024;
+/// Per-subscriber queue length before a slow client starts lagging
+const CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone)]
+pub struct SequencedEvent {
+    pub seq: u64,
+    pub event: UpdateEvent,
+}
+
+/// Events a resuming client missed, or `Resync` when they are no longer buffered
+#[derive(Debug)]
+pub enum Replay {
+    Events(Vec<SequencedEvent>),
+    Resync,
+}
+
+struct History {
+    next_seq: u64,
+    events: VecDeque<SequencedEvent>,
+}
+
+/// Broadcasts update events with monotonic ids and keeps a bounded replay buffer.
+/// Event ids have the form `<epoch>-<seq>` so ids from a previous server run are
+/// recognised and answered with a resync instead of a wrong replay.
+pub struct EventBus {
+    epoch: u64,
+    tx: broadcast::Sender<SequencedEvent>,
+    history: Mutex<History>,
+    capacity: usize,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::with_capacity(REPLAY_CAPACITY)
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            epoch: chrono::Utc::now().timestamp_millis() as u64,
+            tx,
+            history: Mutex::new(History {
+                next_seq: 1,
+                events: VecDeque::with_capacity(capacity),
+            }),
+            capacity,
+        }
+    }
+
+    pub fn publish(&self, event: UpdateEvent) {
+        // Send while holding the lock so the buffer and the channel agree on ordering
+        let mut history = self.history.lock().unwrap();
+        let sequenced = SequencedEvent {
+            seq: history.next_seq,
+            event,
+        };
+        history.next_seq += 1;
+        if history.events.len() == self.capacity {
+            history.events.pop_front();
+        }
+        history.events.push_back(sequenced.clone());
+        let _ = self.tx.send(sequenced);
+    }
+
+    pub fn event_id(&self, seq: u64) -> String {
+        format!("{}-{}", self.epoch, seq)
+    }
+
+    /// Sequence number of the most recently published event, 0 if none
+    pub fn latest_seq(&self) -> u64 {
+        self.history.lock().unwrap().next_seq - 1
+    }
+
+    /// Events published after `seq`, or `Resync` if some of them were dropped
+    pub fn since(&self, seq: u64) -> Replay {
+        let history = self.history.lock().unwrap();
+        Self::replay(&history, seq)
+    }
+
+    fn replay(history: &History, seq: u64) -> Replay {
+        let oldest = history.events.front().map_or(history.next_seq, |e| e.seq);
+        if seq >= history.next_seq || seq + 1 < oldest {
+            return Replay::Resync;
+        }
+        Replay::Events(history.events.iter().filter(|e| e.seq > seq).cloned().collect())
+    }
+
+    /// Subscribe to live events, also returning the latest sequence number at the time of
+    /// subscribing. With a `Last-Event-ID`, also return what the client missed; ids from
+    /// another server run or malformed ids yield `Resync`.
+    pub fn subscribe(&self, last_event_id: Option<&str>) -> (broadcast::Receiver<SequencedEvent>, u64, Replay) {
+        // Subscribe under the lock so nothing published in between is lost
+        let history = self.history.lock().unwrap();
+        let rx = self.tx.subscribe();
+        let seq = history.next_seq - 1;
+
+        let replay = match last_event_id {
+            None => Replay::Events(Vec::new()),
+            Some(id) => match id.split_once('-') {
+                Some((epoch, seq)) if epoch == self.epoch.to_string() => match seq.parse() {
+                    Ok(seq) => Self::replay(&history, seq),
+                    Err(_) => Replay::Resync,
+                },
+                _ => Replay::Resync,
+            },
+        };
+
+        (rx, seq, replay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn idea_deleted(n: u64) -> UpdateEvent {
+        UpdateEvent::idea_deleted(&format!("test/idea-{}", n), "test")
+    }
+
+    fn seqs(replay: Replay) -> Vec<u64> {
+        match replay {
+            Replay::Events(events) => events.iter().map(|e| e.seq).collect(),
+            Replay::Resync => panic!("unexpected resync"),
+        }
+    }
+
+    #[test]
+    fn test_event_filter_matches_source_and_kind() {
+        let event = idea_deleted(1);
+        let reloaded = UpdateEvent::sources_reloaded(&Vec::<String>::new());
+
+        assert!(EventFilter::default().matches(&event));
+        assert!(EventFilter::new(Some("other, test"), None).matches(&event));
+        assert!(!EventFilter::new(Some("other"), None).matches(&event));
+        assert!(EventFilter::new(Some("other"), None).matches(&reloaded));
+
+        assert!(EventFilter::new(None, Some("change,idea")).matches(&event));
+        assert!(EventFilter::new(None, Some("idea.deleted")).matches(&event));
+        assert!(!EventFilter::new(None, Some("idea.created,spec")).matches(&event));
+        assert!(!EventFilter::new(Some("test"), Some("change")).matches(&event));
+        assert!(EventFilter::new(Some(""), Some("")).matches(&event));
+    }
+
+    #[test]
+    fn test_event_bus_replays_missed_events() {
+        let bus = EventBus::with_capacity(3);
+        for n in 1..=2 {
+            bus.publish(idea_deleted(n));
+        }
+
+        let (_, _, replay) = bus.subscribe(Some(&bus.event_id(1)));
+        assert_eq!(seqs(replay), vec![2]);
+        let (_, _, replay) = bus.subscribe(Some(&bus.event_id(2)));
+        assert!(seqs(replay).is_empty());
+        let (_, seq, replay) = bus.subscribe(None);
+        assert_eq!(seq, 2);
+        assert!(seqs(replay).is_empty());
+
+        for n in 3..=5 {
+            bus.publish(idea_deleted(n));
+        }
+        assert_eq!(bus.latest_seq(), 5);
+        assert_eq!(seqs(bus.since(2)), vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_event_bus_resyncs_when_buffer_rolled_over() {
+        let bus = EventBus::with_capacity(3);
+        for n in 1..=5 {
+            bus.publish(idea_deleted(n));
+        }
+
+        assert!(matches!(bus.since(1), Replay::Resync));
+        assert!(matches!(bus.since(9), Replay::Resync));
+        assert!(matches!(bus.subscribe(Some("42-3")).2, Replay::Resync));
+        assert!(matches!(bus.subscribe(Some("garbage")).2, Replay::Resync));
+    }
+
+    #[test]
+    fn test_lagged_subscriber_catches_up_from_its_subscribe_point() {
+        let bus = EventBus::new();
+        for n in 1..=3 {
+            bus.publish(idea_deleted(n));
+        }
+
+        let (mut rx, seq, replay) = bus.subscribe(None);
+        assert_eq!(seq, 3);
+        assert!(seqs(replay).is_empty());
+
+        let total = 3 + CHANNEL_CAPACITY as u64 + 2;
+        for n in 4..=total {
+            bus.publish(idea_deleted(n));
+        }
+        assert!(matches!(rx.try_recv(), Err(broadcast::error::TryRecvError::Lagged(_))));
+
+        // Catching up from the subscribe point yields only what was published since
+        let missed = seqs(bus.since(seq));
+        assert_eq!(missed.first(), Some(&4));
+        assert_eq!(missed.last(), Some(&total));
+    }
+}
@@ -0,0 +1,488 @@
+use crate::config::Source;
+use crate::parser::{self, Change, ChangeDetail, ChangeStatus, Idea, SpecDetail};
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Kanban columns, in board order
+const COLUMNS: [(ChangeStatus, &str); 5] = [
+    (ChangeStatus::Draft, "Draft"),
+    (ChangeStatus::Todo, "Todo"),
+    (ChangeStatus::InProgress, "In Progress"),
+    (ChangeStatus::Done, "Done"),
+    (ChangeStatus::Archived, "Archived"),
+];
+
+const STYLE: &str = r#"*{box-sizing:border-box}
+body{margin:0;font:15px/1.5 system-ui,-apple-system,sans-serif;color:#1f2328;background:#f6f8fa}
+header{background:#24292f;padding:.75rem 1.5rem}
+header a{color:#fff;margin-right:1.25rem;text-decoration:none;font-weight:600}
+main{max-width:1200px;margin:0 auto;padding:1.5rem}
+a{color:#0969da}
+h1{margin-top:0}
+.meta{color:#656d76;font-size:.9em}
+.board{display:grid;grid-template-columns:repeat(5,minmax(180px,1fr));gap:1rem;overflow-x:auto}
+.column{background:#eaeef2;border-radius:6px;padding:.75rem}
+.column h2{font-size:1em;margin:0 0 .5rem}
+.card{display:block;background:#fff;border:1px solid #d0d7de;border-radius:6px;padding:.5rem .75rem;margin-bottom:.5rem;text-decoration:none;color:inherit}
+.card:hover{border-color:#0969da}
+.doc{background:#fff;border:1px solid #d0d7de;border-radius:6px;padding:1rem 1.5rem;margin-bottom:1rem}
+.doc pre{background:#f6f8fa;padding:.75rem;overflow-x:auto}
+.badge{display:inline-block;border-radius:1em;padding:0 .6em;font-size:.8em;background:#ddf4ff;color:#0969da}
+ul.list{list-style:none;padding:0}
+ul.list li{padding:.35rem 0;border-bottom:1px solid #d0d7de}
+#q{width:100%;padding:.5rem;font-size:1em;margin-bottom:1rem}
+"#;
+
+/// Loads `search-index.json` and filters it as the user types
+const SEARCH_SCRIPT: &str = r#"<script>
+fetch('search-index.json').then(r => r.json()).then(entries => {
+  const input = document.getElementById('q');
+  const results = document.getElementById('results');
+  const render = () => {
+    const terms = input.value.toLowerCase().split(/\s+/).filter(Boolean);
+    results.replaceChildren();
+    if (!terms.length) return;
+    for (const e of entries) {
+      const text = (e.title + ' ' + e.text).toLowerCase();
+      if (!terms.every(t => text.includes(t))) continue;
+      const li = document.createElement('li');
+      const a = document.createElement('a');
+      a.href = e.url;
+      a.textContent = e.title;
+      const meta = document.createElement('span');
+      meta.className = 'meta';
+      meta.textContent = ' ' + e.kind + ' · ' + e.id;
+      li.append(a, meta);
+      results.append(li);
+    }
+  };
+  input.addEventListener('input', render);
+  render();
+});
+</script>"#;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ExportSummary {
+    pub changes: usize,
+    pub specs: usize,
+    pub ideas: usize,
+}
+
+#[derive(Serialize)]
+struct SearchEntry {
+    kind: &'static str,
+    id: String,
+    title: String,
+    /// Page path relative to the site root
+    url: String,
+    text: String,
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Whether a link or image destination is relative or uses http(s) or mailto,
+/// so `javascript:` and similar schemes never reach the page
+fn is_safe_url(url: &str) -> bool {
+    let url = url.trim();
+    match url.find([':', '/', '?', '#']) {
+        Some(i) if url[i..].starts_with(':') => {
+            matches!(url[..i].to_ascii_lowercase().as_str(), "http" | "https" | "mailto")
+        }
+        _ => true,
+    }
+}
+
+fn safe_url(url: CowStr<'_>) -> CowStr<'_> {
+    if is_safe_url(&url) {
+        url
+    } else {
+        CowStr::Borrowed("#")
+    }
+}
+
+/// Render markdown to HTML; raw HTML in the source is shown as text and
+/// unsafe link destinations are replaced with `#`
+fn markdown(text: &str) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_TASKLISTS | Options::ENABLE_STRIKETHROUGH;
+    let events = Parser::new_ext(text, options).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        Event::Start(Tag::Link { link_type, dest_url, title, id }) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: safe_url(dest_url),
+            title,
+            id,
+        }),
+        Event::Start(Tag::Image { link_type, dest_url, title, id }) => Event::Start(Tag::Image {
+            link_type,
+            dest_url: safe_url(dest_url),
+            title,
+            id,
+        }),
+        event => event,
+    });
+    let mut out = String::new();
+    html::push_html(&mut out, events);
+    out
+}
+
+/// Keep page paths portable across hosts and file systems
+fn slug_path(id: &str) -> String {
+    id.split('/')
+        .filter(|part| !part.is_empty() && *part != "." && *part != "..")
+        .map(|part| {
+            part.chars()
+                .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '-' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Page paths of every exported entity. Ids that slug to the same path, or to paths
+/// differing only in case (one file on case-insensitive file systems), get a numeric suffix.
+#[derive(Default)]
+struct SiteUrls {
+    /// `(directory, id)` -> page path
+    urls: HashMap<(&'static str, String), String>,
+    taken: HashSet<String>,
+}
+
+impl SiteUrls {
+    fn assign(&mut self, dir: &'static str, id: &str) -> String {
+        let slug = slug_path(id);
+        let mut url = format!("{}/{}.html", dir, slug);
+        let mut n = 2;
+        while !self.taken.insert(url.to_lowercase()) {
+            url = format!("{}/{}-{}.html", dir, slug, n);
+            n += 1;
+        }
+        self.urls.insert((dir, id.to_string()), url.clone());
+        url
+    }
+
+    fn change(&self, change_id: &str) -> Option<&str> {
+        self.urls.get(&("changes", change_id.to_string())).map(String::as_str)
+    }
+
+    fn spec(&self, spec_id: &str) -> Option<&str> {
+        self.urls.get(&("specs", spec_id.to_string())).map(String::as_str)
+    }
+}
+
+/// Prefix that leads from a page back to the site root
+fn root_of(url: &str) -> String {
+    "../".repeat(url.matches('/').count())
+}
+
+fn status_label(status: ChangeStatus) -> &'static str {
+    COLUMNS.iter().find(|(s, _)| *s == status).map_or("", |(_, label)| label)
+}
+
+fn page(root: &str, title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title} · OpenSpec</title>\n<link rel=\"stylesheet\" href=\"{root}style.css\">\n</head>\n<body>\n\
+         <header><a href=\"{root}index.html\">Board</a><a href=\"{root}specs.html\">Specs</a>\
+         <a href=\"{root}ideas.html\">Ideas</a><a href=\"{root}search.html\">Search</a></header>\n\
+         <main>\n{body}</main>\n</body>\n</html>\n",
+        title = escape(title),
+        root = root,
+        body = body,
+    )
+}
+
+fn write_file(out: &Path, url: &str, content: &str) -> std::io::Result<()> {
+    let path = out.join(url);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, content)
+}
+
+fn board(changes: &[Change], ideas: &[Idea], urls: &SiteUrls) -> String {
+    let mut body = String::from("<h1>Changes</h1>\n<div class=\"board\">\n");
+    for (status, label) in COLUMNS {
+        let cards: Vec<(&Change, &str)> = changes
+            .iter()
+            .filter(|c| c.status == status)
+            .filter_map(|c| Some((c, urls.change(&c.id)?)))
+            .collect();
+        let _ = writeln!(body, "<section class=\"column\"><h2>{} ({})</h2>", label, cards.len());
+        for (change, url) in cards {
+            let tasks = change
+                .task_stats
+                .as_ref()
+                .map_or(String::new(), |t| format!("<div class=\"meta\">{}/{} tasks</div>", t.done, t.total));
+            let _ = writeln!(
+                body,
+                "<a class=\"card\" href=\"{}\">{}<div class=\"meta\">{}</div>{}</a>",
+                url,
+                escape(&change.name),
+                escape(&change.source_id),
+                tasks
+            );
+        }
+        body.push_str("</section>\n");
+    }
+    body.push_str("</div>\n");
+    let _ = writeln!(body, "<p class=\"meta\">{} change(s), {} idea(s)</p>", changes.len(), ideas.len());
+    body
+}
+
+fn change_page(root: &str, change: &ChangeDetail, urls: &SiteUrls) -> String {
+    let mut body = format!(
+        "<h1>{}</h1>\n<p><span class=\"badge\">{}</span> <span class=\"meta\">{}</span></p>\n",
+        escape(&change.name),
+        status_label(change.status),
+        escape(&change.id)
+    );
+    if let Some(proposal) = &change.proposal {
+        let _ = writeln!(body, "<section class=\"doc\">{}</section>", markdown(proposal));
+    }
+    if let Some(design) = &change.design {
+        let _ = writeln!(body, "<h2>Design</h2>\n<section class=\"doc\">{}</section>", markdown(design));
+    }
+    if let Some(tasks) = &change.tasks {
+        let _ = writeln!(
+            body,
+            "<h2>Tasks ({}/{})</h2>\n<section class=\"doc\">{}</section>",
+            tasks.stats.done,
+            tasks.stats.total,
+            markdown(&tasks.raw)
+        );
+    }
+    for spec in &change.specs {
+        let capability = spec.path.trim_end_matches("/spec.md").trim_end_matches(".md");
+        let spec_id = format!("{}/{}", change.source_id, capability);
+        let link = urls
+            .spec(&spec_id)
+            .map_or(String::new(), |url| format!(" <a href=\"{}{}\">current spec</a>", root, url));
+        let _ = writeln!(
+            body,
+            "<h2>Delta: specs/{}{}</h2>\n<section class=\"doc\">{}</section>",
+            escape(&spec.path),
+            link,
+            markdown(&spec.content)
+        );
+    }
+    body
+}
+
+fn spec_page(spec: &SpecDetail) -> String {
+    format!(
+        "<p class=\"meta\">{}</p>\n<section class=\"doc\">{}</section>\n",
+        escape(&spec.id),
+        markdown(&spec.content)
+    )
+}
+
+fn idea_page(root: &str, idea: &Idea, urls: &SiteUrls) -> String {
+    let mut body = format!(
+        "<h1>{}</h1>\n<p class=\"meta\">{} · created {}</p>\n",
+        escape(&idea.title),
+        escape(&idea.id),
+        escape(&idea.created_at)
+    );
+    if let Some(change_id) = &idea.promoted_to {
+        match urls.change(change_id) {
+            Some(url) => {
+                let _ = writeln!(body, "<p>Promoted to <a href=\"{}{}\">{}</a></p>", root, url, escape(change_id));
+            }
+            None => {
+                let _ = writeln!(body, "<p>Promoted to {}</p>", escape(change_id));
+            }
+        }
+    }
+    let _ = writeln!(body, "<section class=\"doc\">{}</section>", markdown(&idea.description));
+    body
+}
+
+fn link_list(title: &str, links: &[(String, String, String)]) -> String {
+    let mut body = format!("<h1>{}</h1>\n<ul class=\"list\">\n", escape(title));
+    for (url, label, meta) in links {
+        let _ = writeln!(
+            body,
+            "<li><a href=\"{}\">{}</a> <span class=\"meta\">{}</span></li>",
+            url,
+            escape(label),
+            escape(meta)
+        );
+    }
+    body.push_str("</ul>\n");
+    body
+}
+
+/// Render every change, spec and idea of the valid sources into a static site under `out`
+pub fn export_site(sources: &[Source], out: &Path) -> std::io::Result<ExportSummary> {
+    std::fs::create_dir_all(out)?;
+    let sources: Vec<&Source> = sources.iter().filter(|s| s.valid).collect();
+    let mut summary = ExportSummary::default();
+    let mut entries = Vec::new();
+
+    let mut changes = Vec::new();
+    let mut specs = Vec::new();
+    let mut ideas = Vec::new();
+    for source in &sources {
+        changes.extend(parser::scan_changes(&source.path, &source.id));
+        specs.extend(
+            parser::scan_specs(&source.path, &source.id)
+                .iter()
+                .filter_map(|spec| parser::get_spec_detail(&source.path, &source.id, &spec.path)),
+        );
+        ideas.extend(parser::scan_ideas(&source.path, &source.id));
+    }
+
+    // Changes that cannot be read are left out, and so get no page to link to
+    let details: Vec<(&Change, ChangeDetail)> = changes
+        .iter()
+        .filter_map(|change| {
+            let source = sources.iter().find(|s| s.id == change.source_id)?;
+            Some((change, parser::get_change_detail(&source.path, &source.id, &change.name)?))
+        })
+        .collect();
+    let mut urls = SiteUrls::default();
+    let change_urls: Vec<String> = details.iter().map(|(change, _)| urls.assign("changes", &change.id)).collect();
+    let spec_urls: Vec<String> = specs.iter().map(|spec| urls.assign("specs", &spec.id)).collect();
+    let idea_urls: Vec<String> = ideas.iter().map(|idea| urls.assign("ideas", &idea.id)).collect();
+
+    for ((change, detail), url) in details.iter().zip(change_urls) {
+        let root = root_of(&url);
+        write_file(out, &url, &page(&root, &change.name, &change_page(&root, detail, &urls)))?;
+
+        let text = [&detail.proposal, &detail.design]
+            .into_iter()
+            .flatten()
+            .chain(detail.tasks.as_ref().map(|t| &t.raw))
+            .chain(detail.specs.iter().map(|s| &s.content))
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join("\n");
+        entries.push(SearchEntry { kind: "change", id: change.id.clone(), title: change.name.clone(), url, text });
+        summary.changes += 1;
+    }
+
+    for (spec, url) in specs.iter().zip(&spec_urls) {
+        let url = url.clone();
+        let title = spec.id.split_once('/').map_or(spec.id.as_str(), |(_, name)| name);
+        write_file(out, &url, &page(&root_of(&url), title, &spec_page(spec)))?;
+        entries.push(SearchEntry {
+            kind: "spec",
+            id: spec.id.clone(),
+            title: title.to_string(),
+            url,
+            text: spec.content.clone(),
+        });
+        summary.specs += 1;
+    }
+
+    for (idea, url) in ideas.iter().zip(&idea_urls) {
+        let url = url.clone();
+        let root = root_of(&url);
+        write_file(out, &url, &page(&root, &idea.title, &idea_page(&root, idea, &urls)))?;
+        entries.push(SearchEntry {
+            kind: "idea",
+            id: idea.id.clone(),
+            title: idea.title.clone(),
+            url,
+            text: idea.description.clone(),
+        });
+        summary.ideas += 1;
+    }
+
+    let spec_links: Vec<_> = specs
+        .iter()
+        .zip(spec_urls)
+        .map(|(s, url)| (url, s.id.clone(), s.path.clone()))
+        .collect();
+    let idea_links: Vec<_> = ideas
+        .iter()
+        .zip(idea_urls)
+        .map(|(i, url)| (url, i.title.clone(), format!("{} · {}", i.id, i.created_at)))
+        .collect();
+
+    write_file(out, "style.css", STYLE)?;
+    write_file(out, "index.html", &page("", "Board", &board(&changes, &ideas, &urls)))?;
+    write_file(out, "specs.html", &page("", "Specs", &link_list("Specs", &spec_links)))?;
+    write_file(out, "ideas.html", &page("", "Ideas", &link_list("Ideas", &idea_links)))?;
+    let search = format!(
+        "<h1>Search</h1>\n<input id=\"q\" type=\"search\" placeholder=\"Search changes, specs and ideas\" autofocus>\n\
+         <ul class=\"list\" id=\"results\"></ul>\n{}\n",
+        SEARCH_SCRIPT
+    );
+    write_file(out, "search.html", &page("", "Search", &search))?;
+    let json = serde_json::to_string(&entries).map_err(std::io::Error::other)?;
+    write_file(out, "search-index.json", &json)?;
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_export_site_writes_pages() {
+        let source_dir = TempDir::new("export-src");
+        let out = TempDir::new("export-out");
+
+        let change = source_dir.join("changes/add-login");
+        std::fs::create_dir_all(change.join("specs/auth")).unwrap();
+        std::fs::write(change.join("proposal.md"), "# Change: Add login\n\n<script>alert(1)</script>\n\n[bad](javascript:alert(1)) [docs](https://example.com/docs)\n").unwrap();
+        std::fs::write(change.join("tasks.md"), "- [x] one\n- [ ] two\n").unwrap();
+        std::fs::write(change.join("specs/auth/spec.md"), "## ADDED Requirements\n").unwrap();
+        std::fs::create_dir_all(source_dir.join("specs/auth")).unwrap();
+        std::fs::write(source_dir.join("specs/auth/spec.md"), "# Auth\n\n## Purpose\nLog in.\n").unwrap();
+        std::fs::create_dir_all(source_dir.join("ideas")).unwrap();
+        std::fs::write(
+            source_dir.join("ideas/dark-mode.md"),
+            "---\nid: dark-mode\ncreatedAt: 2025-01-01T00:00:00Z\nupdatedAt: 2025-01-01T00:00:00Z\n---\n# Dark mode\n\nEasier on the eyes.\n",
+        )
+        .unwrap();
+        // Slugs to the same page path as dark-mode
+        std::fs::write(
+            source_dir.join("ideas/dark-mode-copy.md"),
+            "---\nid: dark mode\ncreatedAt: 2025-01-02T00:00:00Z\nupdatedAt: 2025-01-02T00:00:00Z\n---\n# Dark mode copy\n",
+        )
+        .unwrap();
+
+        let source = Source { id: "demo".to_string(), name: "demo".to_string(), path: source_dir.to_path_buf(), valid: true, writable: false };
+        let summary = export_site(&[source], &out).unwrap();
+        assert_eq!(summary, ExportSummary { changes: 1, specs: 1, ideas: 2 });
+
+        let index = std::fs::read_to_string(out.join("index.html")).unwrap();
+        assert!(index.contains("href=\"changes/demo/add-login.html\""));
+        assert!(index.contains("In Progress (1)"));
+
+        let change_page = std::fs::read_to_string(out.join("changes/demo/add-login.html")).unwrap();
+        assert!(change_page.contains("href=\"../../style.css\""));
+        assert!(change_page.contains("&lt;script&gt;"));
+        assert!(change_page.contains("href=\"../../specs/demo/auth.html\""));
+        assert!(!change_page.contains("javascript:"));
+        assert!(change_page.contains("href=\"https://example.com/docs\""));
+
+        assert!(out.join("specs/demo/auth.html").exists());
+        assert!(out.join("ideas/demo/dark-mode.html").exists());
+        assert!(out.join("ideas/demo/dark-mode-2.html").exists());
+        let entries: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(out.join("search-index.json")).unwrap()).unwrap();
+        assert_eq!(entries.as_array().unwrap().len(), 4);
+    }
+}
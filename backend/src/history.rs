@@ -0,0 +1,446 @@
+use crate::config::Source;
+use crate::parser::{self, archived_change_name, Change, ChangeDetail, ChangeStatus, Spec};
+use chrono::{FixedOffset, TimeZone};
+use git2::{DiffOptions, Oid, Repository, Sort, Tree};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+
+/// Timestamps and authorship derived from the commits that touched an entity
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitInfo {
+    /// RFC 3339 date of the oldest commit
+    pub created_at: String,
+    /// RFC 3339 date of the newest commit
+    pub updated_at: String,
+    pub last_author: String,
+    pub commit_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitInfo {
+    pub id: String,
+    pub short_id: String,
+    pub summary: String,
+    pub message: String,
+    pub author: String,
+    pub email: String,
+    /// RFC 3339 author date
+    pub date: String,
+}
+
+#[derive(Debug, Error)]
+pub enum HistoryError {
+    #[error("Source is not inside a git repository")]
+    NotARepository,
+    #[error("Unknown revision: {0}")]
+    UnknownRevision(String),
+    #[error("Git error: {0}")]
+    Git(#[from] git2::Error),
+}
+
+/// One commit that touched a file, with the file's content before and after it;
+/// None when the file did not exist on that side
+#[derive(Debug, Clone)]
+pub struct FileVersion {
+    pub commit: CommitInfo,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// Commits of the repository behind a source that touched files inside it,
+/// newest first, with the files each one changed.
+pub struct SourceHistory {
+    head: Oid,
+    /// Source directory relative to the repository root, `/`-terminated or empty
+    prefix: String,
+    commits: Vec<CommitInfo>,
+    /// Repository-relative file path -> indices into `commits`
+    files: HashMap<String, Vec<usize>>,
+}
+
+fn repo_prefix(repo: &Repository, source_path: &Path) -> Option<String> {
+    let workdir = repo.workdir()?.canonicalize().ok()?;
+    let source = source_path.canonicalize().ok()?;
+    let relative = source.strip_prefix(&workdir).ok()?;
+    let prefix = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    Some(if prefix.is_empty() { prefix } else { prefix + "/" })
+}
+
+/// Repository-relative paths under `prefix` that differ between two trees
+fn changed_paths(repo: &Repository, prefix: &str, old: Option<&Tree>, new: &Tree) -> Option<BTreeSet<String>> {
+    let mut options = DiffOptions::new();
+    if !prefix.is_empty() {
+        options.pathspec(prefix);
+    }
+    let diff = repo.diff_tree_to_tree(old, Some(new), Some(&mut options)).ok()?;
+
+    let mut paths = BTreeSet::new();
+    for delta in diff.deltas() {
+        for file in [delta.old_file(), delta.new_file()] {
+            if let Some(path) = file.path().and_then(|p| p.to_str()) {
+                paths.insert(path.replace('\\', "/"));
+            }
+        }
+    }
+    Some(paths)
+}
+
+fn commit_info(commit: &git2::Commit) -> CommitInfo {
+    let author = commit.author();
+    let time = author.when();
+    let date = FixedOffset::east_opt(time.offset_minutes() * 60)
+        .and_then(|offset| offset.timestamp_opt(time.seconds(), 0).single())
+        .map(|d| d.to_rfc3339())
+        .unwrap_or_default();
+    let id = commit.id().to_string();
+    CommitInfo {
+        short_id: id[..7.min(id.len())].to_string(),
+        id,
+        summary: commit.summary().unwrap_or("").to_string(),
+        message: commit.message().unwrap_or("").trim_end().to_string(),
+        author: author.name().unwrap_or("").to_string(),
+        email: author.email().unwrap_or("").to_string(),
+        date,
+    }
+}
+
+impl SourceHistory {
+    /// Walk the history of the repository containing `source_path`; None when
+    /// the source is not inside a git work tree or has no commits yet.
+    pub fn load(source_path: &Path) -> Option<Self> {
+        let repo = Repository::discover(source_path).ok()?;
+        let prefix = repo_prefix(&repo, source_path)?;
+        let head = repo.head().ok()?.peel_to_commit().ok()?.id();
+
+        let mut walk = repo.revwalk().ok()?;
+        walk.set_sorting(Sort::TIME).ok()?;
+        walk.push(head).ok()?;
+
+        let mut history = SourceHistory {
+            head,
+            prefix: prefix.clone(),
+            commits: Vec::new(),
+            files: HashMap::new(),
+        };
+        for oid in walk.flatten() {
+            let Ok(commit) = repo.find_commit(oid) else {
+                continue;
+            };
+            let Ok(tree) = commit.tree() else {
+                continue;
+            };
+            // A merge only touches the files that differ from every parent, the way
+            // `git log` simplifies history; the rest came in with the merged commits
+            let parent_trees: Vec<Option<Tree>> = match commit.parent_count() {
+                0 => vec![None],
+                _ => commit.parents().map(|p| p.tree().ok()).collect(),
+            };
+            let mut touched: Option<BTreeSet<String>> = None;
+            for parent_tree in parent_trees {
+                let Some(paths) = changed_paths(&repo, &prefix, parent_tree.as_ref(), &tree) else {
+                    continue;
+                };
+                touched = Some(match touched {
+                    Some(touched) => touched.intersection(&paths).cloned().collect(),
+                    None => paths,
+                });
+            }
+            let paths = touched.unwrap_or_default();
+            if paths.is_empty() {
+                continue;
+            }
+
+            let index = history.commits.len();
+            history.commits.push(commit_info(&commit));
+            for path in paths {
+                history.files.entry(path).or_default().push(index);
+            }
+        }
+        Some(history)
+    }
+
+    /// Indices of commits touching any of the source-relative paths, where a
+    /// path is either a file or a directory, newest first
+    fn commits_touching(&self, paths: &[String]) -> Vec<usize> {
+        let targets: Vec<String> = paths.iter().map(|p| format!("{}{}", self.prefix, p)).collect();
+        let mut indices = BTreeSet::new();
+        for (file, commits) in &self.files {
+            let matches = targets.iter().any(|target| {
+                file == target || (file.starts_with(target.as_str()) && file[target.len()..].starts_with('/'))
+            });
+            if matches {
+                indices.extend(commits);
+            }
+        }
+        indices.into_iter().collect()
+    }
+
+    fn info(&self, indices: &[usize]) -> Option<GitInfo> {
+        let newest = &self.commits[*indices.first()?];
+        let oldest = &self.commits[*indices.last()?];
+        Some(GitInfo {
+            created_at: oldest.date.clone(),
+            updated_at: newest.date.clone(),
+            last_author: newest.author.clone(),
+            commit_count: indices.len(),
+        })
+    }
+
+    /// Directories a change has lived in; archived changes include their
+    /// pre-archive location so their history starts when they were proposed
+    fn change_paths(change_name: &str, archived: bool) -> Vec<String> {
+        if archived {
+            vec![
+                format!("changes/archive/{}", change_name),
+                format!("changes/{}", archived_change_name(change_name)),
+            ]
+        } else {
+            vec![format!("changes/{}", change_name)]
+        }
+    }
+
+    pub fn change_info(&self, change_name: &str, archived: bool) -> Option<GitInfo> {
+        self.info(&self.commits_touching(&Self::change_paths(change_name, archived)))
+    }
+
+    pub fn change_commits(&self, change_name: &str, archived: bool) -> Vec<CommitInfo> {
+        self.commits_touching(&Self::change_paths(change_name, archived))
+            .into_iter()
+            .map(|i| self.commits[i].clone())
+            .collect()
+    }
+
+    /// `spec_path` is the source-relative path of the spec file
+    pub fn file_info(&self, spec_path: &str) -> Option<GitInfo> {
+        self.info(&self.commits_touching(&[spec_path.to_string()]))
+    }
+
+    /// Every commit that touched a source-relative file, newest first, with
+    /// the file content on both sides of the commit
+    pub fn file_versions(&self, source_path: &Path, file: &str) -> Result<Vec<FileVersion>, HistoryError> {
+        let repo = Repository::discover(source_path).map_err(|_| HistoryError::NotARepository)?;
+        let path = format!("{}{}", self.prefix, file);
+        let mut versions = Vec::new();
+        for index in self.commits_touching(&[file.to_string()]) {
+            let info = &self.commits[index];
+            let commit = repo.find_commit(Oid::from_str(&info.id)?)?;
+            let before = match commit.parent(0) {
+                Ok(parent) => blob_text(&repo, &parent, &path)?,
+                Err(_) => None,
+            };
+            versions.push(FileVersion {
+                commit: info.clone(),
+                before,
+                after: blob_text(&repo, &commit, &path)?,
+            });
+        }
+        Ok(versions)
+    }
+}
+
+fn blob_text(repo: &Repository, commit: &git2::Commit, path: &str) -> Result<Option<String>, HistoryError> {
+    let entry = match commit.tree()?.get_path(Path::new(path)) {
+        Ok(entry) => entry,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let blob = entry.to_object(repo)?.peel_to_blob()?;
+    Ok(Some(String::from_utf8_lossy(blob.content()).into_owned()))
+}
+
+/// Content of a source-relative file at a revision such as `HEAD~2`, a branch
+/// or a commit id, along with the commit it resolved to
+pub fn file_at_revision(source_path: &Path, rev: &str, file: &str) -> Result<(CommitInfo, Option<String>), HistoryError> {
+    let repo = Repository::discover(source_path).map_err(|_| HistoryError::NotARepository)?;
+    let prefix = repo_prefix(&repo, source_path).ok_or(HistoryError::NotARepository)?;
+    let commit = repo
+        .revparse_single(rev)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|_| HistoryError::UnknownRevision(rev.to_string()))?;
+    let text = blob_text(&repo, &commit, &format!("{}{}", prefix, file))?;
+    Ok((commit_info(&commit), text))
+}
+
+/// Source-relative file of a spec, resolved like `get_spec_detail`
+pub fn spec_file(source_path: &Path, spec_path: &str) -> String {
+    match parser::resolve_spec(source_path, spec_path) {
+        Some(path) if path == source_path.join(spec_path) => spec_path.to_string(),
+        _ => format!("specs/{}", spec_path),
+    }
+}
+
+fn current_head(source_path: &Path) -> Option<Oid> {
+    let repo = Repository::discover(source_path).ok()?;
+    let head = repo.head().ok()?.target();
+    head
+}
+
+/// Per-source history, reloaded when the repository's HEAD moves.
+/// Loading walks the whole repository, so async callers should go through
+/// `AppState::with_history` rather than calling into this directly.
+#[derive(Default)]
+pub struct HistoryCache {
+    sources: Mutex<HashMap<PathBuf, Arc<SourceHistory>>>,
+    /// Held while a source's history loads, so concurrent misses share one walk
+    loading: Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>,
+}
+
+impl HistoryCache {
+    fn cached(&self, source_path: &Path, head: Oid) -> Option<Arc<SourceHistory>> {
+        let sources = self.sources.lock().unwrap();
+        sources.get(source_path).filter(|history| history.head == head).cloned()
+    }
+
+    pub fn get(&self, source_path: &Path) -> Option<Arc<SourceHistory>> {
+        let head = current_head(source_path)?;
+        if let Some(history) = self.cached(source_path, head) {
+            return Some(history);
+        }
+
+        let loading = self.loading.lock().unwrap().entry(source_path.to_path_buf()).or_default().clone();
+        let _loading = loading.lock().unwrap();
+        if let Some(history) = self.cached(source_path, head) {
+            return Some(history);
+        }
+
+        let history = Arc::new(SourceHistory::load(source_path)?);
+        self.sources
+            .lock()
+            .unwrap()
+            .insert(source_path.to_path_buf(), history.clone());
+        Some(history)
+    }
+
+    pub fn annotate_changes(&self, sources: &[Source], changes: &mut [Change]) {
+        for source in sources {
+            let Some(history) = self.get(&source.path) else {
+                continue;
+            };
+            for change in changes.iter_mut().filter(|c| c.source_id == source.id) {
+                change.git = history.change_info(&change.name, change.status == ChangeStatus::Archived);
+            }
+        }
+    }
+
+    pub fn annotate_specs(&self, sources: &[Source], specs: &mut [Spec]) {
+        for source in sources {
+            let Some(history) = self.get(&source.path) else {
+                continue;
+            };
+            for spec in specs.iter_mut().filter(|s| s.source_id == source.id) {
+                spec.git = history.file_info(&spec_file(&source.path, &spec.path));
+            }
+        }
+    }
+
+    pub fn annotate_change_detail(&self, source_path: &Path, detail: &mut ChangeDetail) {
+        if let Some(history) = self.get(source_path) {
+            detail.git = history.change_info(&detail.name, detail.status == ChangeStatus::Archived);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use git2::{Signature, Time};
+
+    fn commit(repo: &Repository, message: &str, author: &str, seconds: i64) {
+        let mut index = repo.index().unwrap();
+        index.add_all(["*"], git2::IndexAddOption::DEFAULT, None).unwrap();
+        index.update_all(["*"], None).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::new(author, "dev@example.com", &Time::new(seconds, 0)).unwrap();
+        let parents: Vec<git2::Commit> = repo.head().ok().and_then(|h| h.peel_to_commit().ok()).into_iter().collect();
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents).unwrap();
+    }
+
+    #[test]
+    fn test_source_history() {
+        let root = TempDir::new("history");
+        let source = root.join("openspec");
+        std::fs::create_dir_all(source.join("changes/add-login")).unwrap();
+        std::fs::create_dir_all(source.join("specs/auth")).unwrap();
+        let repo = Repository::init(&root).unwrap();
+
+        std::fs::write(source.join("changes/add-login/proposal.md"), "# Change: Add login\n").unwrap();
+        std::fs::write(root.join("README.md"), "outside the source\n").unwrap();
+        commit(&repo, "Propose login", "Ada", 1_700_000_000);
+
+        std::fs::write(root.join("README.md"), "still outside\n").unwrap();
+        commit(&repo, "Touch readme", "Grace", 1_700_100_000);
+
+        std::fs::write(source.join("changes/add-login/tasks.md"), "- [ ] one\n").unwrap();
+        std::fs::write(source.join("specs/auth/spec.md"), "# Auth\n").unwrap();
+        commit(&repo, "Add tasks", "Linus", 1_700_200_000);
+
+        let history = SourceHistory::load(&source).unwrap();
+        assert_eq!(history.commits.len(), 2);
+
+        let info = history.change_info("add-login", false).unwrap();
+        assert_eq!(info.commit_count, 2);
+        assert_eq!(info.last_author, "Linus");
+        assert!(info.created_at.starts_with("2023-11-14"));
+        assert!(info.updated_at.starts_with("2023-11-17"));
+
+        let commits = history.change_commits("add-login", false);
+        assert_eq!(commits.iter().map(|c| c.summary.as_str()).collect::<Vec<_>>(), ["Add tasks", "Propose login"]);
+
+        // Archived changes keep the history of their pre-archive directory
+        std::fs::create_dir_all(source.join("changes/archive")).unwrap();
+        std::fs::rename(source.join("changes/add-login"), source.join("changes/archive/2023-11-20-add-login")).unwrap();
+        commit(&repo, "Archive login", "Ada", 1_700_400_000);
+        let cache = HistoryCache::default();
+        let history = cache.get(&source).unwrap();
+        assert_eq!(history.change_info("2023-11-20-add-login", true).unwrap().commit_count, 3);
+        assert_eq!(history.file_info(&spec_file(&source, "auth/spec.md")).unwrap().commit_count, 1);
+        assert!(history.change_info("add-logout", false).is_none());
+
+        let versions = history.file_versions(&source, "specs/auth/spec.md").unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!((versions[0].before.as_deref(), versions[0].after.as_deref()), (None, Some("# Auth\n")));
+        let (commit, text) = file_at_revision(&source, "HEAD~1", "changes/add-login/tasks.md").unwrap();
+        assert_eq!((commit.summary.as_str(), text.as_deref()), ("Add tasks", Some("- [ ] one\n")));
+        assert!(file_at_revision(&source, "HEAD", "changes/add-login/tasks.md").unwrap().1.is_none());
+        assert!(matches!(file_at_revision(&source, "nope", "x"), Err(HistoryError::UnknownRevision(_))));
+
+        // A merge that only brings in a branch's changes does not count as touching them
+        let main = repo.head().unwrap().peel_to_commit().unwrap();
+        std::fs::write(source.join("specs/auth/spec.md"), "# Auth\n\nBranch edit\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_all(["*"], git2::IndexAddOption::DEFAULT, None).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::new("Grace", "dev@example.com", &Time::new(1_700_500_000, 0)).unwrap();
+        let branch = repo.commit(None, &signature, &signature, "Edit auth", &tree, &[&main]).unwrap();
+        let branch = repo.find_commit(branch).unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Merge branch", &tree, &[&main, &branch])
+            .unwrap();
+
+        // Concurrent misses wait for a single load and share its result
+        let loaded: Vec<Arc<SourceHistory>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4).map(|_| scope.spawn(|| cache.get(&source).unwrap())).collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert!(loaded.iter().all(|history| Arc::ptr_eq(history, &loaded[0])));
+        let summaries: Vec<String> = loaded[0]
+            .file_versions(&source, "specs/auth/spec.md")
+            .unwrap()
+            .into_iter()
+            .map(|v| v.commit.summary)
+            .collect();
+        assert_eq!(summaries, ["Edit auth", "Add tasks"]);
+    }
+}
@@ -0,0 +1,560 @@
+use crate::config::Source;
+use crate::events::{EventKind, UpdateEvent};
+use crate::parser::{self, Change, ChangeStatus, Idea, Spec};
+use crate::paths;
+use crate::search::{Document, EntityKind, SearchHit, SearchIndex};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Component, Path, PathBuf},
+};
+
+/// Cached scan results for one source.
+/// Changes are refreshed one directory at a time since each one parses its tasks.md;
+/// specs and ideas are cheap to list and are rescanned per source.
+#[derive(Default, Clone)]
+struct SourceIndex {
+    /// Keyed by `<name>` for active changes and `archive/<name>` for archived ones
+    changes: BTreeMap<String, Change>,
+    specs: Vec<Spec>,
+    ideas: Vec<Idea>,
+}
+
+#[derive(Clone)]
+struct IndexedSource {
+    source: Source,
+    /// Canonical source path, used to map watcher paths back to the source
+    root: PathBuf,
+    index: SourceIndex,
+}
+
+/// In-memory index of changes, specs and ideas for every valid source.
+/// Built once per source list and updated incrementally from watcher paths.
+pub struct Index {
+    sources: Vec<IndexedSource>,
+    search: SearchIndex,
+}
+
+/// Copies of the indexed sources, refreshed from disk without holding the index lock
+pub struct IndexSnapshot {
+    sources: Vec<IndexedSource>,
+}
+
+/// Sources refreshed from a snapshot, ready to be swapped into the index
+pub struct IndexUpdate {
+    /// Position in `Index::sources`, the refreshed source, its events and search edits
+    sources: Vec<(usize, IndexedSource, Vec<UpdateEvent>, Vec<SearchUpdate>)>,
+}
+
+/// A search index edit worked out while refreshing, applied once the index is locked
+enum SearchUpdate {
+    Upsert(Document),
+    Remove(EntityKind, String),
+}
+
+/// Resolve `path` to an absolute, canonical form even if it no longer exists,
+/// by canonicalizing its closest existing ancestor.
+fn normalize(path: &Path) -> PathBuf {
+    let mut existing = path.to_path_buf();
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return rest.iter().rev().fold(canonical, |acc, part| acc.join(part));
+        }
+        match (existing.file_name().map(|n| n.to_os_string()), existing.parent()) {
+            (Some(name), Some(parent)) => {
+                rest.push(name);
+                existing = parent.to_path_buf();
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+fn is_markdown(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "md")
+}
+
+fn change_key(name: &str, archived: bool) -> String {
+    if archived {
+        format!("archive/{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Whether an archive directory such as `2026-01-02-add-foo` holds the change `add-foo`
+fn is_archive_of(archived_name: &str, name: &str) -> bool {
+    archived_name == name || archived_name.ends_with(&format!("-{}", name))
+}
+
+impl IndexedSource {
+    fn build(source: Source) -> Self {
+        let root = normalize(&source.path);
+        let mut indexed = Self {
+            source,
+            root,
+            index: SourceIndex::default(),
+        };
+        indexed.rescan_changes();
+        indexed.rescan_specs();
+        indexed.rescan_ideas();
+        indexed
+    }
+
+    fn change_dir(&self, change: &Change) -> PathBuf {
+        let changes = self.source.path.join("changes");
+        if change.status == ChangeStatus::Archived {
+            changes.join("archive").join(&change.name)
+        } else {
+            changes.join(&change.name)
+        }
+    }
+
+    fn change_document(&self, change: &Change) -> Document {
+        let dir = self.change_dir(change);
+        let mut fields: Vec<(String, String)> = ["proposal", "design", "tasks"]
+            .iter()
+            .filter_map(|name| {
+                let text = paths::read_to_string(&self.source.path, &dir.join(format!("{}.md", name))).ok()?;
+                Some((name.to_string(), text))
+            })
+            .collect();
+
+        let mut deltas: Vec<PathBuf> = walkdir::WalkDir::new(dir.join("specs"))
+            .into_iter()
+            .flatten()
+            .map(|e| e.into_path())
+            .filter(|p| p.is_file() && is_markdown(p))
+            .collect();
+        deltas.sort();
+        for path in deltas {
+            if let (Ok(relative), Ok(text)) = (path.strip_prefix(&dir), paths::read_to_string(&self.source.path, &path)) {
+                fields.push((relative.display().to_string(), text));
+            }
+        }
+
+        Document {
+            kind: EntityKind::Change,
+            id: change.id.clone(),
+            source_id: change.source_id.clone(),
+            title: change.name.clone(),
+            fields,
+        }
+    }
+
+    fn spec_document(&self, spec: &Spec) -> Document {
+        let content = parser::get_spec_detail(&self.source.path, &self.source.id, &spec.path)
+            .map(|d| d.content)
+            .unwrap_or_default();
+        let title = spec.id.strip_prefix(&format!("{}/", spec.source_id)).unwrap_or(&spec.id);
+        Document {
+            kind: EntityKind::Spec,
+            id: spec.id.clone(),
+            source_id: spec.source_id.clone(),
+            title: title.to_string(),
+            fields: vec![("spec".to_string(), content)],
+        }
+    }
+
+    fn idea_document(idea: &Idea) -> Document {
+        Document {
+            kind: EntityKind::Idea,
+            id: idea.id.clone(),
+            source_id: idea.source_id.clone(),
+            title: idea.title.clone(),
+            fields: vec![("idea".to_string(), idea.description.clone())],
+        }
+    }
+
+    fn index_all(&self, search: &mut SearchIndex) {
+        for change in self.index.changes.values() {
+            search.upsert(self.change_document(change));
+        }
+        for spec in &self.index.specs {
+            search.upsert(self.spec_document(spec));
+        }
+        for idea in &self.index.ideas {
+            search.upsert(Self::idea_document(idea));
+        }
+    }
+
+    /// Search index edits that bring it in line with the entries an event describes
+    fn search_updates(&self, event: &UpdateEvent) -> Vec<SearchUpdate> {
+        let mut updates = Vec::new();
+        match event.kind {
+            EventKind::ChangeCreated | EventKind::ChangeUpdated | EventKind::ChangeArchived => {
+                if let Some(previous) = &event.previous_id {
+                    updates.push(SearchUpdate::Remove(EntityKind::Change, previous.clone()));
+                }
+                if let Some(change) = self.index.changes.values().find(|c| c.id == event.id) {
+                    updates.push(SearchUpdate::Upsert(self.change_document(change)));
+                }
+            }
+            EventKind::ChangeDeleted => updates.push(SearchUpdate::Remove(EntityKind::Change, event.id.clone())),
+            EventKind::SpecCreated | EventKind::SpecUpdated => {
+                if let Some(spec) = self.index.specs.iter().find(|s| s.id == event.id) {
+                    updates.push(SearchUpdate::Upsert(self.spec_document(spec)));
+                }
+            }
+            EventKind::SpecDeleted => updates.push(SearchUpdate::Remove(EntityKind::Spec, event.id.clone())),
+            EventKind::IdeaCreated | EventKind::IdeaUpdated => {
+                if let Some(idea) = self.index.ideas.iter().find(|i| i.id == event.id) {
+                    updates.push(SearchUpdate::Upsert(Self::idea_document(idea)));
+                }
+            }
+            EventKind::IdeaDeleted => updates.push(SearchUpdate::Remove(EntityKind::Idea, event.id.clone())),
+            EventKind::SourcesReloaded => {}
+        }
+        updates
+    }
+
+    fn rescan_changes(&mut self) {
+        self.index.changes = parser::scan_changes(&self.source.path, &self.source.id)
+            .into_iter()
+            .map(|change| (change_key(&change.name, change.status == ChangeStatus::Archived), change))
+            .collect();
+    }
+
+    fn refresh_change(&mut self, name: &str, archived: bool) {
+        let key = change_key(name, archived);
+        let path = if archived {
+            self.source.path.join("changes").join("archive").join(name)
+        } else {
+            self.source.path.join("changes").join(name)
+        };
+        match parser::scan_change(&self.source.path, &path, &self.source.id, archived) {
+            Some(change) => self.index.changes.insert(key, change),
+            None => self.index.changes.remove(&key),
+        };
+    }
+
+    fn rescan_specs(&mut self) {
+        self.index.specs = parser::scan_specs(&self.source.path, &self.source.id);
+    }
+
+    fn rescan_ideas(&mut self) {
+        self.index.ideas = parser::scan_ideas(&self.source.path, &self.source.id);
+    }
+
+    /// Work needed to bring the index up to date after a change at `relative`
+    /// (a path relative to the source root)
+    fn refresh_for(relative: &Path) -> Option<Refresh> {
+        let parts: Vec<&str> = relative
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(part) => part.to_str(),
+                _ => None,
+            })
+            .collect();
+
+        match parts.as_slice() {
+            [] => Some(Refresh::All),
+            ["changes"] | ["changes", "archive"] => Some(Refresh::Changes),
+            ["changes", "archive", name, ..] => Some(Refresh::Change(name.to_string(), true)),
+            ["changes", name, ..] => Some(Refresh::Change(name.to_string(), false)),
+            ["specs", ..] => Some(Refresh::Specs),
+            ["ideas", ..] => Some(Refresh::Ideas),
+            [_] if is_markdown(relative) => Some(Refresh::Specs),
+            _ => None,
+        }
+    }
+
+    /// Refresh the entries affected by the given paths (relative to the source root)
+    /// and describe what changed as typed events.
+    fn apply(&mut self, relatives: &[&Path]) -> Vec<UpdateEvent> {
+        // A burst of events usually touches the same few entries; refresh each once
+        let mut refreshes: BTreeSet<Refresh> = relatives.iter().filter_map(|r| Self::refresh_for(r)).collect();
+        if refreshes.contains(&Refresh::All) {
+            refreshes = BTreeSet::from([Refresh::All]);
+        }
+
+        let touched: BTreeSet<String> = refreshes
+            .iter()
+            .filter_map(|r| match r {
+                Refresh::Change(name, archived) => Some(change_key(name, *archived)),
+                _ => None,
+            })
+            .collect();
+
+        let before = self.index.clone();
+        for refresh in refreshes {
+            self.refresh(refresh);
+        }
+
+        let mut events = Vec::new();
+        self.diff_changes(&before.changes, &touched, &mut events);
+        self.diff_specs(&before.specs, relatives, &mut events);
+        self.diff_ideas(&before.ideas, &mut events);
+        events
+    }
+
+    fn diff_changes(&self, before: &BTreeMap<String, Change>, touched: &BTreeSet<String>, events: &mut Vec<UpdateEvent>) {
+        let mut archived = Vec::new();
+        for (key, change) in &self.index.changes {
+            match before.get(key) {
+                None if change.status == ChangeStatus::Archived => archived.push(change),
+                None => events.push(UpdateEvent::change(EventKind::ChangeCreated, change)),
+                // Edits to proposal/design/specs leave the summary as is but still count as updates
+                Some(old) if old != change || touched.contains(key) => {
+                    events.push(UpdateEvent::change(EventKind::ChangeUpdated, change))
+                }
+                Some(_) => {}
+            }
+        }
+
+        let mut removed: Vec<&Change> = before
+            .iter()
+            .filter(|(key, _)| !self.index.changes.contains_key(*key))
+            .map(|(_, change)| change)
+            .collect();
+
+        // Moving a change into archive/ shows up as a removal plus an archived addition
+        for change in archived {
+            let mut event = UpdateEvent::change(EventKind::ChangeArchived, change);
+            if let Some(i) = removed
+                .iter()
+                .position(|old| old.status != ChangeStatus::Archived && is_archive_of(&change.name, &old.name))
+            {
+                event.previous_id = Some(removed.remove(i).id.clone());
+            }
+            events.push(event);
+        }
+
+        for old in removed {
+            events.push(UpdateEvent::change_deleted(&old.id, &old.source_id));
+        }
+    }
+
+    fn diff_specs(&self, before: &[Spec], relatives: &[&Path], events: &mut Vec<UpdateEvent>) {
+        for spec in &self.index.specs {
+            if !before.contains(spec) {
+                events.push(UpdateEvent::spec(EventKind::SpecCreated, spec));
+                continue;
+            }
+            let root_path = Path::new(&spec.path);
+            let nested_path = Path::new("specs").join(&spec.path);
+            if relatives.iter().any(|r| *r == root_path || *r == nested_path) {
+                events.push(UpdateEvent::spec(EventKind::SpecUpdated, spec));
+            }
+        }
+
+        for old in before.iter().filter(|s| !self.index.specs.contains(s)) {
+            events.push(UpdateEvent::spec_deleted(&old.id, &old.source_id));
+        }
+    }
+
+    fn diff_ideas(&self, before: &[Idea], events: &mut Vec<UpdateEvent>) {
+        for idea in &self.index.ideas {
+            match before.iter().find(|old| old.id == idea.id) {
+                None => events.push(UpdateEvent::idea(EventKind::IdeaCreated, idea)),
+                Some(old) if old != idea => events.push(UpdateEvent::idea(EventKind::IdeaUpdated, idea)),
+                Some(_) => {}
+            }
+        }
+
+        for old in before.iter().filter(|old| !self.index.ideas.iter().any(|i| i.id == old.id)) {
+            events.push(UpdateEvent::idea_deleted(&old.id, &old.source_id));
+        }
+    }
+
+    fn refresh(&mut self, refresh: Refresh) {
+        match refresh {
+            Refresh::All => {
+                self.rescan_changes();
+                self.rescan_specs();
+                self.rescan_ideas();
+            }
+            Refresh::Changes => self.rescan_changes(),
+            Refresh::Change(name, archived) => self.refresh_change(&name, archived),
+            Refresh::Specs => self.rescan_specs(),
+            Refresh::Ideas => self.rescan_ideas(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Refresh {
+    All,
+    Changes,
+    Change(String, bool),
+    Specs,
+    Ideas,
+}
+
+impl IndexSnapshot {
+    /// Re-read the entries for the given changed filesystem paths.
+    /// Paths outside every indexed source are ignored.
+    pub fn refresh(self, paths: &[PathBuf]) -> IndexUpdate {
+        let paths: Vec<PathBuf> = paths.iter().map(|p| normalize(p)).collect();
+        let mut sources = Vec::new();
+
+        for (i, mut indexed) in self.sources.into_iter().enumerate() {
+            let relatives: Vec<&Path> = paths
+                .iter()
+                .filter_map(|p| p.strip_prefix(&indexed.root).ok())
+                .collect();
+            if !relatives.is_empty() {
+                let applied = indexed.apply(&relatives);
+                let search = applied.iter().flat_map(|event| indexed.search_updates(event)).collect();
+                sources.push((i, indexed, applied, search));
+            }
+        }
+
+        IndexUpdate { sources }
+    }
+}
+
+impl Index {
+    /// Scan every valid source from disk
+    pub fn build(sources: &[Source]) -> Self {
+        let sources: Vec<IndexedSource> = sources
+            .iter()
+            .filter(|s| s.valid)
+            .cloned()
+            .map(IndexedSource::build)
+            .collect();
+        let mut search = SearchIndex::default();
+        for indexed in &sources {
+            indexed.index_all(&mut search);
+        }
+        Self { sources, search }
+    }
+
+    /// Copy the indexed entries so they can be refreshed from disk without holding
+    /// a lock on the index; see `IndexSnapshot::refresh` and `Index::install`
+    pub fn snapshot(&self) -> IndexSnapshot {
+        IndexSnapshot {
+            sources: self.sources.clone(),
+        }
+    }
+
+    /// Swap in sources refreshed from a snapshot and return the events describing
+    /// what changed. Sources no longer indexed since the snapshot are skipped.
+    pub fn install(&mut self, update: IndexUpdate) -> Vec<UpdateEvent> {
+        let mut events = Vec::new();
+        for (i, indexed, applied, search) in update.sources {
+            let Some(slot) = self.sources.get_mut(i).filter(|slot| slot.source == indexed.source) else {
+                continue;
+            };
+            *slot = indexed;
+            for update in search {
+                match update {
+                    SearchUpdate::Upsert(document) => self.search.upsert(document),
+                    SearchUpdate::Remove(kind, id) => self.search.remove(kind, &id),
+                }
+            }
+            events.extend(applied);
+        }
+        events
+    }
+
+    pub fn changes(&self) -> Vec<Change> {
+        self.sources
+            .iter()
+            .flat_map(|s| s.index.changes.values().cloned())
+            .collect()
+    }
+
+    pub fn specs(&self) -> Vec<Spec> {
+        self.sources
+            .iter()
+            .flat_map(|s| s.index.specs.iter().cloned())
+            .collect()
+    }
+
+    pub fn ideas(&self) -> Vec<Idea> {
+        self.sources
+            .iter()
+            .flat_map(|s| s.index.ideas.iter().cloned())
+            .collect()
+    }
+
+    /// Full-text search; returns the total number of matches and the top `limit` hits
+    pub fn search(&self, query: &str, sources: Option<&[String]>, limit: usize) -> (usize, Vec<SearchHit>) {
+        self.search.search(query, sources, limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn apply_paths(index: &mut Index, paths: &[PathBuf]) -> Vec<UpdateEvent> {
+        let update = index.snapshot().refresh(paths);
+        index.install(update)
+    }
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_index_applies_incremental_paths() {
+        let root = TempDir::new("index");
+        write(&root.join("changes/add-foo/proposal.md"), "# Add foo");
+        write(&root.join("specs/chat/spec.md"), "# chat");
+
+        let source = Source {
+            id: "test".to_string(),
+            name: "test".to_string(),
+            path: root.to_path_buf(),
+            valid: true,
+            writable: true,
+        };
+        let mut index = Index::build(&[source]);
+        assert_eq!(index.changes().len(), 1);
+        assert_eq!(index.specs().len(), 1);
+        assert!(index.ideas().is_empty());
+
+        write(&root.join("changes/add-foo/tasks.md"), "- [x] 1.1 Done\n- [ ] 1.2 Todo\n");
+        write(&root.join("changes/add-bar/proposal.md"), "# Add bar");
+        write(
+            &root.join("ideas/idea-1.md"),
+            "---\nid: idea-1\ncreatedAt: 2026-01-01\nupdatedAt: 2026-01-01\n---\n\n# Idea\n",
+        );
+        let events = apply_paths(&mut index, &[
+            root.join("changes/add-foo/tasks.md"),
+            root.join("changes/add-bar"),
+            root.join("ideas/idea-1.md"),
+        ]);
+        let kinds: Vec<(&str, &str)> = events.iter().map(|e| (e.kind.as_str(), e.id.as_str())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("change.created", "test/add-bar"),
+                ("change.updated", "test/add-foo"),
+                ("idea.created", "test/idea-1"),
+            ]
+        );
+
+        assert_eq!(index.search("todo", None, 10).1[0].id, "test/add-foo");
+
+        let changes = index.changes();
+        assert_eq!(changes.len(), 2);
+        let foo = changes.iter().find(|c| c.name == "add-foo").unwrap();
+        assert_eq!(foo.task_stats.as_ref().map(|s| s.done), Some(1));
+        assert_eq!(index.ideas()[0].id, "test/idea-1");
+
+        std::fs::create_dir_all(root.join("changes/archive")).unwrap();
+        std::fs::rename(root.join("changes/add-bar"), root.join("changes/archive/2026-01-01-add-bar")).unwrap();
+        let events = apply_paths(&mut index, &[
+            root.join("changes/add-bar"),
+            root.join("changes/archive/2026-01-01-add-bar"),
+        ]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, EventKind::ChangeArchived);
+        assert_eq!(events[0].previous_id.as_deref(), Some("test/add-bar"));
+        let (_, hits) = index.search("bar", None, 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id, "test/2026-01-01-add-bar");
+
+        std::fs::remove_dir_all(root.join("specs/chat")).unwrap();
+        let events = apply_paths(&mut index, &[root.join("specs/chat/spec.md")]);
+        assert_eq!(events[0].kind, EventKind::SpecDeleted);
+        assert!(index.specs().is_empty());
+
+        assert!(apply_paths(&mut index, &[std::env::temp_dir().join("elsewhere.md")]).is_empty());
+    }
+}
@@ -1,24 +1,47 @@
+mod auth;
+mod cli;
 mod config;
 mod config_manager;
+mod events;
+mod export;
+mod history;
+mod index;
 mod parser;
+mod paths;
+mod report;
+mod search;
+mod spec;
+mod tasks;
+#[cfg(test)]
+mod test_support;
+mod validate;
 
 use axum::{
-    extract::{Path, State},
-    http::{header, StatusCode, Uri},
-    response::{sse::Event, IntoResponse, Json, Sse},
-    routing::{delete, get, put},
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode, Uri},
+    middleware,
+    response::{sse::Event, IntoResponse, Json, Response, Sse},
+    routing::{delete, get, patch, post, put},
     Router,
 };
-use clap::Parser as ClapParser;
-use config::SourceConfig;
+use auth::Auth;
+use clap::{Parser as ClapParser, Subcommand};
+use config::{ConfigError, ConfigFormat, Source, SourceConfig};
 use config_manager::{AppState, ConfigManager, ConfigResponse};
+use events::{EventBus, EventFilter, Replay, SequencedEvent, UpdateEvent};
 use futures::stream::{self, Stream};
 use notify::{EventKind, RecursiveMode, Watcher};
 use notify_debouncer_full::{new_debouncer, DebouncedEvent, FileIdMap};
-use parser::{Change, ChangeDetail, Idea, Spec, SpecDetail};
+use parser::{ArchiveError, ArchivedChange, Change, ChangeDetail, CreateChangeError, PromoteIdeaError, Idea, Spec, SpecDetail, TaskUpdateError, TasksContent};
+use report::{ReportFormat, ReportRange};
+use history::{CommitInfo, HistoryError};
+use search::SearchHit;
+use spec::{MergedSpec, RequirementDiff};
+use validate::{Diagnostic, SourceDiagnostics};
 use rust_embed::RustEmbed;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::{HashMap, HashSet, VecDeque},
     convert::Infallible,
     env,
     net::SocketAddr,
@@ -26,13 +49,13 @@ use std::{
     sync::Arc,
     time::Duration,
 };
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
 use tower_http::{
     cors::{Any, CorsLayer},
     services::ServeDir,
 };
 use tower_http::cors::AllowOrigin;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{filter::LevelFilter, layer::SubscriberExt, util::SubscriberInitExt, Layer};
 
 #[derive(RustEmbed)]
 #[folder = "../frontend/dist"]
@@ -43,8 +66,31 @@ struct Assets;
 #[command(about = "A read-only dashboard for OpenSpec")]
 struct Args {
     /// Path to config file
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     config: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Start the dashboard server (the default)
+    Serve,
+    /// Lint OpenSpec files; exits 1 on errors and 2 on usage problems
+    Validate(cli::ValidateArgs),
+    /// List changes, specs or ideas from the configured sources
+    List(cli::ListArgs),
+    /// Show a change, spec or idea by id
+    Show(cli::ShowArgs),
+    /// Render all sources into a static HTML site
+    ExportSite(cli::ExportArgs),
+    /// Print a status report of changes, tasks and ideas
+    Report(cli::ReportArgs),
+    /// Hash a password read from stdin for an `auth.users` entry
+    HashPassword,
+    /// Check the config file or print its JSON Schema
+    #[command(subcommand)]
+    Config(cli::ConfigCommand),
 }
 
 // AppState is now defined in config_manager module
@@ -57,11 +103,17 @@ struct SourceResponse {
     name: String,
     path: String,
     valid: bool,
+    writable: bool,
 }
 
 #[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
 struct SourcesResponse {
     sources: Vec<SourceResponse>,
+    read_only: bool,
+    /// Set while the config file on disk is invalid and the previous config is still in use
+    #[serde(skip_serializing_if = "Option::is_none")]
+    config_error: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -79,6 +131,65 @@ struct ChangesResponse {
     changes: Vec<Change>,
 }
 
+#[derive(Serialize)]
+struct HistoryResponse {
+    commits: Vec<CommitInfo>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpecVersion {
+    commit: CommitInfo,
+    /// False when this commit deleted the spec
+    exists: bool,
+    /// Requirement changes made by this commit
+    requirements: Vec<RequirementDiff>,
+}
+
+#[derive(Serialize)]
+struct SpecVersionsResponse {
+    versions: Vec<SpecVersion>,
+}
+
+#[derive(Deserialize)]
+struct SpecDiffQuery {
+    from: String,
+    /// Defaults to the file currently on disk
+    to: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpecRevision {
+    rev: String,
+    /// None for the working tree
+    commit: Option<CommitInfo>,
+    exists: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SpecDiffResponse {
+    id: String,
+    path: String,
+    from: SpecRevision,
+    to: SpecRevision,
+    requirements: Vec<RequirementDiff>,
+    unified_diff: String,
+    additions: usize,
+    deletions: usize,
+}
+
+#[derive(Serialize)]
+struct MergedSpecsResponse {
+    specs: Vec<MergedSpec>,
+}
+
+#[derive(Serialize)]
+struct DiagnosticsResponse {
+    diagnostics: Vec<Diagnostic>,
+}
+
 #[derive(Serialize)]
 struct SpecsResponse {
     specs: Vec<Spec>,
@@ -98,6 +209,87 @@ struct CreateIdeaRequest {
     source_id: Option<String>,
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateChangeRequest {
+    source_id: String,
+    /// Kebab-case change name, e.g. `add-user-auth`
+    name: String,
+    #[serde(default)]
+    proposal: Option<String>,
+    #[serde(default)]
+    design: bool,
+    /// Capability to create a delta spec skeleton for
+    #[serde(default)]
+    capability: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct ArchiveChangeRequest {
+    /// Archive even with unfinished tasks; conflicting deltas are always refused
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Deserialize)]
+struct UpdateTaskRequest {
+    /// `version` of the tasks.md content the client last read
+    version: String,
+    /// Desired state; the checkbox is flipped when omitted
+    #[serde(default)]
+    checked: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+    /// Comma-separated source ids
+    source: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct ReportQuery {
+    #[serde(default)]
+    format: ReportFormat,
+    /// Inclusive `YYYY-MM-DD` bounds for archived changes and new ideas
+    from: Option<String>,
+    to: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SearchResponse {
+    query: String,
+    total: usize,
+    hits: Vec<SearchHit>,
+}
+
+#[derive(Deserialize)]
+struct EventsQuery {
+    /// Comma-separated source ids
+    source: Option<String>,
+    /// Comma-separated categories (`change`) or event names (`change.updated`)
+    kind: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct PromoteIdeaRequest {
+    /// Change name; derived from the idea title when omitted
+    #[serde(default)]
+    name: Option<String>,
+    /// Delete the idea instead of marking it as promoted
+    #[serde(default)]
+    delete_idea: bool,
+}
+
+#[derive(Serialize)]
+struct PromoteIdeaResponse {
+    change: Change,
+    /// The idea marked as promoted, or null if it was deleted
+    idea: Option<Idea>,
+}
+
 #[derive(Deserialize)]
 struct UpdateIdeaRequest {
     title: String,
@@ -110,36 +302,101 @@ async fn health() -> &'static str {
     "ok"
 }
 
-async fn get_sources(State(state): State<AppState>) -> Json<SourcesResponse> {
-    let sources = state.get_sources().await;
-    let response = sources
+fn source_responses(sources: &[Source]) -> Vec<SourceResponse> {
+    sources
         .iter()
         .map(|s| SourceResponse {
             id: s.id.clone(),
             name: s.name.clone(),
             path: s.path.display().to_string(),
             valid: s.valid,
+            writable: s.writable,
         })
-        .collect();
-    Json(SourcesResponse { sources: response })
+        .collect()
+}
+
+async fn get_sources(State(state): State<AppState>) -> Json<SourcesResponse> {
+    let sources = state.get_sources().await;
+    Json(SourcesResponse {
+        sources: source_responses(&sources),
+        read_only: state.read_only().await,
+        config_error: state.config_error().await,
+    })
 }
 
 async fn get_changes(State(state): State<AppState>) -> Json<ChangesResponse> {
-    let mut all_changes = Vec::new();
+    let mut changes = state.index.read().await.changes();
     let sources = state.get_sources().await;
+    let changes = state
+        .with_history(move |history| {
+            history.annotate_changes(&sources, &mut changes);
+            changes
+        })
+        .await;
+    Json(ChangesResponse { changes })
+}
 
-    for source in sources.iter().filter(|s| s.valid) {
-        let changes = parser::scan_changes(&source.path, &source.id);
-        all_changes.extend(changes);
+async fn get_change_detail(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ChangeDetail>, StatusCode> {
+    // id format: source_id/change_name
+    let parts: Vec<&str> = id.splitn(2, '/').collect();
+    if parts.len() != 2 {
+        return Err(StatusCode::BAD_REQUEST);
     }
 
-    Json(ChangesResponse { changes: all_changes })
+    let source_id = parts[0];
+    let change_name = parts[1];
+
+    let sources = state.get_sources().await;
+    let source = sources
+        .iter()
+        .find(|s| s.id == source_id && s.valid)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let mut detail = parser::get_change_detail(&source.path, source_id, change_name).ok_or(StatusCode::NOT_FOUND)?;
+    let source_path = source.path.clone();
+    let detail = state
+        .with_history(move |history| {
+            history.annotate_change_detail(&source_path, &mut detail);
+            detail
+        })
+        .await;
+    Ok(Json(detail))
 }
 
-async fn get_change_detail(
+async fn get_change_history(
     State(state): State<AppState>,
     Path(id): Path<String>,
-) -> Result<Json<ChangeDetail>, StatusCode> {
+) -> Result<Json<HistoryResponse>, StatusCode> {
+    let (source_id, change_name) = id.split_once('/').ok_or(StatusCode::BAD_REQUEST)?;
+
+    let sources = state.get_sources().await;
+    let source = sources
+        .iter()
+        .find(|s| s.id == source_id && s.valid)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let (dir, archived) = parser::find_change_dir(&source.path, change_name).ok_or(StatusCode::NOT_FOUND)?;
+    let dir_name = dir.file_name().and_then(|n| n.to_str()).ok_or(StatusCode::NOT_FOUND)?.to_string();
+    let source_path = source.path.clone();
+    // Sources outside a git repository simply have no history
+    let commits = state
+        .with_history(move |history| {
+            history
+                .get(&source_path)
+                .map(|history| history.change_commits(&dir_name, archived))
+                .unwrap_or_default()
+        })
+        .await;
+    Ok(Json(HistoryResponse { commits }))
+}
+
+async fn get_merged_specs(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<MergedSpecsResponse>, StatusCode> {
     // id format: source_id/change_name
     let parts: Vec<&str> = id.splitn(2, '/').collect();
     if parts.len() != 2 {
@@ -155,21 +412,146 @@ async fn get_change_detail(
         .find(|s| s.id == source_id && s.valid)
         .ok_or(StatusCode::NOT_FOUND)?;
 
-    parser::get_change_detail(&source.path, source_id, change_name)
-        .map(Json)
+    parser::get_merged_specs(&source.path, source_id, change_name)
+        .map(|specs| Json(MergedSpecsResponse { specs }))
         .ok_or(StatusCode::NOT_FOUND)
 }
 
-async fn get_specs(State(state): State<AppState>) -> Json<SpecsResponse> {
-    let mut all_specs = Vec::new();
+async fn get_change_diagnostics(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<DiagnosticsResponse>, StatusCode> {
+    let (source_id, change_name) = id.split_once('/').ok_or(StatusCode::BAD_REQUEST)?;
+
+    let sources = state.get_sources().await;
+    let source = sources
+        .iter()
+        .find(|s| s.id == source_id && s.valid)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    validate::check_change(&source.path, change_name)
+        .map(|diagnostics| Json(DiagnosticsResponse { diagnostics }))
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn archive_change(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    req: Option<Json<ArchiveChangeRequest>>,
+) -> Result<Json<ArchivedChange>, (StatusCode, Json<ErrorResponse>)> {
+    let Json(req) = req.unwrap_or_default();
+    let Some((source_id, change_name)) = id.split_once('/') else {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Invalid change ID format".to_string(),
+            }),
+        ));
+    };
+
+    let sources = state.get_sources().await;
+    let source = sources
+        .iter()
+        .find(|s| s.id == source_id && s.valid)
+        .ok_or_else(|| (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Source not found".to_string(),
+            }),
+        ))?;
+    ensure_writable(&state, source).await?;
+
+    let archived = parser::archive_change(&source.path, &source.id, change_name, req.force).map_err(|e| {
+        let status = match e {
+            ArchiveError::ChangeNotFound => StatusCode::NOT_FOUND,
+            ArchiveError::InvalidPath(_) => StatusCode::BAD_REQUEST,
+            ArchiveError::AlreadyArchived
+            | ArchiveError::IncompleteTasks { .. }
+            | ArchiveError::Conflicts(_)
+            | ArchiveError::ArchiveExists(_) => StatusCode::CONFLICT,
+            ArchiveError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(ErrorResponse { error: e.to_string() }))
+    })?;
+
+    let changes = source.path.join("changes");
+    let mut paths = vec![
+        changes.join(change_name),
+        changes.join("archive").join(&archived.change.name),
+    ];
+    paths.extend(archived.specs.iter().map(|s| source.path.join("specs").join(&s.path)));
+    state.refresh_paths(&paths).await;
+
+    Ok(Json(archived))
+}
+
+async fn update_task(
+    State(state): State<AppState>,
+    Path((id, task_id)): Path<(String, String)>,
+    Json(req): Json<UpdateTaskRequest>,
+) -> Result<Json<TasksContent>, (StatusCode, Json<ErrorResponse>)> {
+    let parts: Vec<&str> = id.splitn(2, '/').collect();
+    if parts.len() != 2 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Invalid change ID format".to_string(),
+            }),
+        ));
+    }
+
+    let source_id = parts[0];
+    let change_name = parts[1];
+
     let sources = state.get_sources().await;
+    let source = sources
+        .iter()
+        .find(|s| s.id == source_id && s.valid)
+        .ok_or_else(|| (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Source not found".to_string(),
+            }),
+        ))?;
+    ensure_writable(&state, source).await?;
 
-    for source in sources.iter().filter(|s| s.valid) {
-        let specs = parser::scan_specs(&source.path, &source.id);
-        all_specs.extend(specs);
+    // Hold the tasks.md lock so two requests with the same version cannot both pass the check
+    let change_dir = parser::find_change_dir(&source.path, change_name);
+    let lock = match &change_dir {
+        Some((change_path, _)) => Some(state.lock_file(&change_path.join("tasks.md")).await),
+        None => None,
+    };
+    let tasks = parser::update_task(&source.path, change_name, &task_id, &req.version, req.checked)
+        .map_err(|e| {
+            let status = match e {
+                TaskUpdateError::ChangeNotFound
+                | TaskUpdateError::TasksNotFound
+                | TaskUpdateError::TaskNotFound(_) => StatusCode::NOT_FOUND,
+                TaskUpdateError::VersionMismatch | TaskUpdateError::ChangeArchived => StatusCode::CONFLICT,
+                TaskUpdateError::InvalidPath(_) => StatusCode::BAD_REQUEST,
+                TaskUpdateError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, Json(ErrorResponse { error: e.to_string() }))
+        })?;
+
+    drop(lock);
+    if let Some((change_path, _)) = change_dir {
+        state.refresh_paths(&[change_path.join("tasks.md")]).await;
     }
 
-    Json(SpecsResponse { specs: all_specs })
+    Ok(Json(tasks))
+}
+
+async fn get_specs(State(state): State<AppState>) -> Json<SpecsResponse> {
+    let mut specs = state.index.read().await.specs();
+    let sources = state.get_sources().await;
+    let specs = state
+        .with_history(move |history| {
+            history.annotate_specs(&sources, &mut specs);
+            specs
+        })
+        .await;
+    Json(SpecsResponse { specs })
 }
 
 async fn get_spec_detail(
@@ -207,16 +589,259 @@ async fn get_spec_detail(
     Err(StatusCode::NOT_FOUND)
 }
 
+fn history_error(error: HistoryError) -> (StatusCode, Json<ErrorResponse>) {
+    let status = match &error {
+        HistoryError::NotARepository => StatusCode::NOT_FOUND,
+        HistoryError::UnknownRevision(_) => StatusCode::BAD_REQUEST,
+        HistoryError::Git(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(ErrorResponse { error: error.to_string() }))
+}
+
+/// Resolve a spec id to its source and detail, trying the same path formats as get_spec_detail
+async fn find_spec(state: &AppState, id: &str) -> Result<(Source, SpecDetail), (StatusCode, Json<ErrorResponse>)> {
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse { error: format!("Spec not found: {}", id) }),
+        )
+    };
+    let (source_id, spec_name) = id.split_once('/').ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse { error: "Spec id must look like <source>/<name>".to_string() }),
+        )
+    })?;
+
+    let sources = state.get_sources().await;
+    let source = sources
+        .into_iter()
+        .find(|s| s.id == source_id && s.valid)
+        .ok_or_else(not_found)?;
+    let detail = [format!("{}/spec.md", spec_name), format!("{}.md", spec_name), spec_name.to_string()]
+        .iter()
+        .find_map(|spec_path| parser::get_spec_detail(&source.path, source_id, spec_path))
+        .ok_or_else(not_found)?;
+    Ok((source, detail))
+}
+
+async fn get_spec_versions(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<SpecVersionsResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let (source, spec) = find_spec(&state, &id).await?;
+    let versions = state
+        .with_history(move |history| {
+            // Sources outside a git repository simply have no versions
+            let Some(history) = history.get(&source.path) else {
+                return Ok(Vec::new());
+            };
+            history.file_versions(&source.path, &history::spec_file(&source.path, &spec.path))
+        })
+        .await
+        .map_err(history_error)?
+        .into_iter()
+        .map(|version| SpecVersion {
+            requirements: spec::diff_spec(
+                version.before.as_deref().unwrap_or(""),
+                version.after.as_deref().unwrap_or(""),
+            ),
+            exists: version.after.is_some(),
+            commit: version.commit,
+        })
+        .collect();
+    Ok(Json(SpecVersionsResponse { versions }))
+}
+
+async fn get_spec_diff(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<SpecDiffQuery>,
+) -> Result<Json<SpecDiffResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let (source, spec) = find_spec(&state, &id).await?;
+    let file = history::spec_file(&source.path, &spec.path);
+
+    // Both revisions are read on the blocking pool, like every other git lookup
+    let (from_revision, to_revision) = {
+        let (file, from_rev, to_rev) = (file.clone(), query.from.clone(), query.to.clone());
+        tokio::task::spawn_blocking(move || {
+            let from = history::file_at_revision(&source.path, &from_rev, &file)?;
+            let to = to_rev.map(|rev| history::file_at_revision(&source.path, &rev, &file)).transpose()?;
+            Ok((from, to))
+        })
+        .await
+        .expect("history task panicked")
+        .map_err(history_error)?
+    };
+
+    let (from_commit, before) = from_revision;
+    let from = SpecRevision {
+        rev: query.from,
+        exists: before.is_some(),
+        commit: Some(from_commit),
+    };
+    let (to, after) = match (query.to, to_revision) {
+        (Some(rev), Some((commit, after))) => {
+            (SpecRevision { rev, exists: after.is_some(), commit: Some(commit) }, after)
+        }
+        _ => (
+            SpecRevision { rev: "worktree".to_string(), exists: true, commit: None },
+            Some(spec.content),
+        ),
+    };
+
+    let (before, after) = (before.unwrap_or_default(), after.unwrap_or_default());
+    let (unified_diff, additions, deletions) = spec::line_diff(
+        &before,
+        &after,
+        &format!("a/{} ({})", file, from.rev),
+        &format!("b/{} ({})", file, to.rev),
+    );
+    Ok(Json(SpecDiffResponse {
+        id: spec.id,
+        path: spec.path,
+        requirements: spec::diff_spec(&before, &after),
+        from,
+        to,
+        unified_diff,
+        additions,
+        deletions,
+    }))
+}
+
+async fn get_spec_diagnostics(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<DiagnosticsResponse>, StatusCode> {
+    let (source_id, spec_name) = id.split_once('/').ok_or(StatusCode::BAD_REQUEST)?;
+
+    let sources = state.get_sources().await;
+    let source = sources
+        .iter()
+        .find(|s| s.id == source_id && s.valid)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    // Same path formats as get_spec_detail
+    [format!("{}/spec.md", spec_name), format!("{}.md", spec_name), spec_name.to_string()]
+        .iter()
+        .find_map(|spec_path| validate::check_spec(&source.path, spec_path))
+        .map(|diagnostics| Json(DiagnosticsResponse { diagnostics }))
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn get_source_diagnostics(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<SourceDiagnostics>, StatusCode> {
+    let sources = state.get_sources().await;
+    let source = sources
+        .iter()
+        .find(|s| s.id == id && s.valid)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(validate::check_source(&source.path, &source.id)))
+}
+
 async fn get_ideas(State(state): State<AppState>) -> Json<IdeasResponse> {
-    let mut all_ideas = Vec::new();
+    let ideas = state.index.read().await.ideas();
+    Json(IdeasResponse { ideas })
+}
+
+async fn search(State(state): State<AppState>, Query(query): Query<SearchQuery>) -> Json<SearchResponse> {
+    let sources: Option<Vec<String>> = query
+        .source
+        .map(|s| s.split(',').map(|id| id.trim().to_string()).filter(|id| !id.is_empty()).collect())
+        .filter(|ids: &Vec<String>| !ids.is_empty());
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+    let (total, hits) = state.index.read().await.search(&query.q, sources.as_deref(), limit);
+    Json(SearchResponse {
+        query: query.q,
+        total,
+        hits,
+    })
+}
+
+async fn get_status_report(
+    State(state): State<AppState>,
+    Query(query): Query<ReportQuery>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let today = chrono::Local::now().date_naive();
+    let range = ReportRange::parse(query.from.as_deref(), query.to.as_deref(), today)
+        .map_err(|error| (StatusCode::BAD_REQUEST, Json(ErrorResponse { error })))?;
+
     let sources = state.get_sources().await;
+    let (changes, ideas) = {
+        let index = state.index.read().await;
+        (index.changes(), index.ideas())
+    };
+    let report = report::build(&sources, &changes, &ideas, range);
 
-    for source in sources.iter().filter(|s| s.valid) {
-        let ideas = parser::scan_ideas(&source.path, &source.id);
-        all_ideas.extend(ideas);
+    let body = match query.format {
+        ReportFormat::Json => return Ok(Json(report).into_response()),
+        ReportFormat::Md => report::to_markdown(&report),
+        ReportFormat::Csv => report::to_csv(&report),
+    };
+    Ok(([(header::CONTENT_TYPE, query.format.content_type())], body).into_response())
+}
+
+/// 403 with an explanation unless writes to `source` are allowed
+async fn ensure_writable(state: &AppState, source: &Source) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
+    if source.writable {
+        return Ok(());
     }
+    let error = if state.read_only().await {
+        "Server is in read-only mode".to_string()
+    } else {
+        format!("Source '{}' is read-only", source.id)
+    };
+    Err((StatusCode::FORBIDDEN, Json(ErrorResponse { error })))
+}
+
+async fn create_change(
+    State(state): State<AppState>,
+    Json(req): Json<CreateChangeRequest>,
+) -> Result<(StatusCode, Json<Change>), (StatusCode, Json<ErrorResponse>)> {
+    let sources = state.get_sources().await;
+    let source = sources
+        .iter()
+        .find(|s| s.id == req.source_id && s.valid)
+        .ok_or_else(|| (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!("Source '{}' not found", req.source_id),
+            }),
+        ))?;
+    ensure_writable(&state, source).await?;
+
+    let new = parser::NewChange {
+        name: &req.name,
+        proposal: req.proposal.as_deref(),
+        design: req.design,
+        capability: req.capability.as_deref().filter(|c| !c.is_empty()),
+        origin_idea: None,
+    };
+    let change = parser::create_change(&source.path, &source.id, &new).map_err(|e| {
+        let status = match e {
+            CreateChangeError::InvalidName(_) | CreateChangeError::InvalidPath(_) => StatusCode::BAD_REQUEST,
+            CreateChangeError::AlreadyExists(_) => StatusCode::CONFLICT,
+            CreateChangeError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(ErrorResponse { error: e.to_string() }))
+    })?;
+
+    state.refresh_paths(&[source.path.join("changes").join(&change.name)]).await;
+
+    Ok((StatusCode::CREATED, Json(change)))
+}
 
-    Json(IdeasResponse { ideas: all_ideas })
+/// Status for a failed idea write: bad ids are the client's fault, a missing file is a 404
+fn io_error_status(error: &std::io::Error) -> StatusCode {
+    match error.kind() {
+        std::io::ErrorKind::InvalidInput => StatusCode::BAD_REQUEST,
+        std::io::ErrorKind::NotFound => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
 }
 
 async fn create_idea(
@@ -236,10 +861,11 @@ async fn create_idea(
                 }),
             ))?
     } else {
-        // Default to first valid source if none specified
+        // Default to the first writable source if none specified
         sources
             .iter()
-            .find(|s| s.valid)
+            .find(|s| s.valid && s.writable)
+            .or_else(|| sources.iter().find(|s| s.valid))
             .ok_or_else(|| (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ErrorResponse {
@@ -247,6 +873,7 @@ async fn create_idea(
                 }),
             ))?
     };
+    ensure_writable(&state, source).await?;
 
     let id = format!("idea-{}", chrono::Utc::now().timestamp_millis());
     let idea = parser::save_idea(
@@ -258,13 +885,13 @@ async fn create_idea(
         None
     )
         .map_err(|e| (
-            StatusCode::INTERNAL_SERVER_ERROR,
+            io_error_status(&e),
             Json(ErrorResponse {
                 error: format!("Failed to save idea: {}", e),
             }),
         ))?;
 
-    let _ = state.update_tx.send(());
+    state.refresh_paths(&[source.path.join("ideas").join(format!("{}.md", id))]).await;
 
     Ok(Json(idea))
 }
@@ -296,16 +923,17 @@ async fn delete_idea(
                 error: "Source not found".to_string(),
             }),
         ))?;
+    ensure_writable(&state, source).await?;
 
     parser::delete_idea(&source.path, idea_id)
         .map_err(|e| (
-            StatusCode::INTERNAL_SERVER_ERROR,
+            io_error_status(&e),
             Json(ErrorResponse {
                 error: format!("Failed to delete idea: {}", e),
             }),
         ))?;
 
-    let _ = state.update_tx.send(());
+    state.refresh_paths(&[source.path.join("ideas").join(format!("{}.md", idea_id))]).await;
 
     Ok(StatusCode::OK)
 }
@@ -338,34 +966,130 @@ async fn update_idea(
                 error: "Source not found".to_string(),
             }),
         ))?;
+    ensure_writable(&state, source).await?;
 
     let idea = parser::update_idea(&source.path, &source.id, idea_id, &req.title, &req.description)
         .map_err(|e| (
-            StatusCode::INTERNAL_SERVER_ERROR,
+            io_error_status(&e),
             Json(ErrorResponse {
                 error: format!("Failed to update idea: {}", e),
             }),
         ))?;
 
-    let _ = state.update_tx.send(());
+    state.refresh_paths(&[source.path.join("ideas").join(format!("{}.md", idea_id))]).await;
 
     Ok(Json(idea))
 }
 
-async fn get_config(State(state): State<AppState>) -> Result<Json<ConfigResponse>, StatusCode> {
+async fn promote_idea(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    req: Option<Json<PromoteIdeaRequest>>,
+) -> Result<(StatusCode, Json<PromoteIdeaResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let Json(req) = req.unwrap_or_default();
+    let Some((source_id, idea_id)) = id.split_once('/') else {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Invalid idea ID format".to_string(),
+            }),
+        ));
+    };
+
+    let sources = state.get_sources().await;
+    let source = sources
+        .iter()
+        .find(|s| s.id == source_id && s.valid)
+        .ok_or_else(|| (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Source not found".to_string(),
+            }),
+        ))?;
+    ensure_writable(&state, source).await?;
+
+    let (change, idea) = parser::promote_idea(&source.path, &source.id, idea_id, req.name.as_deref(), req.delete_idea)
+        .map_err(|e| {
+            let status = match e {
+                PromoteIdeaError::IdeaNotFound => StatusCode::NOT_FOUND,
+                PromoteIdeaError::AlreadyPromoted(_) | PromoteIdeaError::Create(CreateChangeError::AlreadyExists(_)) => {
+                    StatusCode::CONFLICT
+                }
+                PromoteIdeaError::Create(CreateChangeError::InvalidName(_) | CreateChangeError::InvalidPath(_))
+                | PromoteIdeaError::InvalidPath(_) => StatusCode::BAD_REQUEST,
+                PromoteIdeaError::Create(CreateChangeError::Io(_)) | PromoteIdeaError::Io(_) => {
+                    StatusCode::INTERNAL_SERVER_ERROR
+                }
+            };
+            (status, Json(ErrorResponse { error: e.to_string() }))
+        })?;
+
+    state
+        .refresh_paths(&[
+            source.path.join("changes").join(&change.name),
+            source.path.join("ideas").join(format!("{}.md", idea_id)),
+        ])
+        .await;
+
+    Ok((StatusCode::CREATED, Json(PromoteIdeaResponse { change, idea })))
+}
+
+async fn get_config(State(state): State<AppState>) -> Result<Json<ConfigResponse>, (StatusCode, Json<ErrorResponse>)> {
     let config_manager = state.config_manager().await;
-    config_manager
-        .get_config_response()
-        .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+    config_manager.get_config_response().map(Json).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Invalid config file: {}", e),
+            }),
+        )
+    })
+}
+
+/// Reload the config file and tell SSE clients when the sources changed
+async fn reload_config(state: &AppState) -> Result<(), String> {
+    if let Some(sources) = state.reload_config().await? {
+        state.publish(UpdateEvent::sources_reloaded(&source_responses(&sources)));
+    }
+    Ok(())
 }
 
 async fn update_sources(
     State(state): State<AppState>,
     Json(req): Json<UpdateSourcesRequest>,
 ) -> Result<Json<ConfigResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if state.read_only().await {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: "Server is in read-only mode; edit the config file instead".to_string(),
+            }),
+        ));
+    }
+
     let config_manager = state.config_manager().await;
 
+    // Only JSON configs are rewritten; YAML and TOML would lose their comments
+    let format = config_manager.format();
+    if format != ConfigFormat::Json {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: ConfigError::HandEdited(format.name()).to_string(),
+            }),
+        ));
+    }
+
+    // Names double as source ids, so a duplicate would make two sources indistinguishable
+    if let Some(name) = config::duplicate_source_name(&req.sources) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: ConfigError::DuplicateSource(name.to_string()).to_string(),
+            }),
+        ));
+    }
+
     // Validate sources - invalid ones are filtered out with warnings
     let (valid_sources, warnings) = config_manager.validate_sources(&req.sources);
 
@@ -385,26 +1109,17 @@ async fn update_sources(
         ));
     }
 
-    // Reload sources and update state
-    let new_sources = config_manager
-        .load_sources()
-        .map_err(|e| {
-            tracing::error!("Failed to reload sources: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: format!("Failed to reload sources: {}", e),
-                }),
-            )
-        })?;
-
-    state.update_sources(new_sources).await;
-
-    // Trigger file watcher restart and SSE update
-    let inner = state.inner.read().await;
-    let _ = inner.config_update_tx.send(());
-    drop(inner);
-    let _ = state.update_tx.send(());
+    // Reload sources, restart the affected watchers and notify SSE clients.
+    // The config file watcher sees this write too, but finds nothing left to apply.
+    reload_config(&state).await.map_err(|e| {
+        tracing::error!("Failed to reload sources: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Failed to reload sources: {}", e),
+            }),
+        )
+    })?;
 
     // Return updated config
     config_manager
@@ -421,17 +1136,88 @@ async fn update_sources(
         })
 }
 
+fn sse_event(bus: &EventBus, sequenced: &SequencedEvent) -> Event {
+    let data = serde_json::to_string(&sequenced.event).unwrap_or_default();
+    Event::default()
+        .id(bus.event_id(sequenced.seq))
+        .event(sequenced.event.kind.as_str())
+        .data(data)
+}
+
+/// Ask the client to refetch everything; carries the latest id so it can resume from there
+fn resync_event(bus: &EventBus, seq: u64, reason: &str) -> Event {
+    Event::default().id(bus.event_id(seq)).event("resync").data(reason)
+}
+
+struct SseStream {
+    bus: Arc<EventBus>,
+    rx: broadcast::Receiver<SequencedEvent>,
+    filter: EventFilter,
+    pending: VecDeque<Event>,
+    /// Highest sequence number already delivered (or skipped by a resync)
+    last_seq: u64,
+}
+
+impl SseStream {
+    fn queue_replay(&mut self, replay: Replay, reason: &str) {
+        match replay {
+            Replay::Events(events) => {
+                for sequenced in events {
+                    self.last_seq = sequenced.seq;
+                    if self.filter.matches(&sequenced.event) {
+                        self.pending.push_back(sse_event(&self.bus, &sequenced));
+                    }
+                }
+            }
+            Replay::Resync => {
+                self.last_seq = self.bus.latest_seq();
+                self.pending.push_back(resync_event(&self.bus, self.last_seq, reason));
+            }
+        }
+    }
+}
+
 async fn sse_handler(
     State(state): State<AppState>,
+    Query(query): Query<EventsQuery>,
+    headers: HeaderMap,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
-    let rx = state.update_tx.subscribe();
+    let last_event_id = headers.get("last-event-id").and_then(|v| v.to_str().ok());
+    let bus = state.events.clone();
+    let (rx, last_seq, replay) = bus.subscribe(last_event_id);
+
+    let mut initial = SseStream {
+        last_seq,
+        bus,
+        rx,
+        filter: EventFilter::new(query.source.as_deref(), query.kind.as_deref()),
+        pending: VecDeque::new(),
+    };
+    initial.queue_replay(replay, "expired");
 
-    let stream = stream::unfold(rx, |mut rx| async move {
-        match rx.recv().await {
-            Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {
-                Some((Ok(Event::default().event("update").data("changed")), rx))
+    let stream = stream::unfold(initial, |mut s| async move {
+        loop {
+            if let Some(event) = s.pending.pop_front() {
+                return Some((Ok(event), s));
+            }
+            match s.rx.recv().await {
+                // Skip anything already sent as part of a replay
+                Ok(sequenced) if sequenced.seq <= s.last_seq => continue,
+                Ok(sequenced) if !s.filter.matches(&sequenced.event) => {
+                    s.last_seq = sequenced.seq;
+                }
+                Ok(sequenced) => {
+                    s.last_seq = sequenced.seq;
+                    let event = sse_event(&s.bus, &sequenced);
+                    return Some((Ok(event), s));
+                }
+                // Fill the gap from the replay buffer, or resync if it has rolled over
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    let replay = s.bus.since(s.last_seq);
+                    s.queue_replay(replay, "lagged");
+                }
+                Err(_) => return None,
             }
-            Err(_) => None,
         }
     });
 
@@ -470,20 +1256,118 @@ async fn static_handler(uri: Uri) -> impl IntoResponse {
     }
 }
 
+/// Config path from `--config`, `OPENSPEC_UI_CONFIG` or the default file name
+fn resolve_config_path(config: Option<PathBuf>) -> PathBuf {
+    config
+        .or_else(|| env::var("OPENSPEC_UI_CONFIG").ok().map(PathBuf::from))
+        .unwrap_or_else(config::default_config_path)
+}
+
+/// Sources for a CLI subcommand: explicit paths, or else the configured sources
+fn cli_sources(config: Option<PathBuf>, paths: &[PathBuf]) -> Result<Vec<Source>, String> {
+    if !paths.is_empty() {
+        return Ok(cli::sources_from_paths(paths));
+    }
+    let config_path = resolve_config_path(config);
+    if !config_path.exists() {
+        return Err(format!("Config file not found: {:?}", config_path));
+    }
+    ConfigManager::new(config_path)
+        .load_sources()
+        .map_err(|e| format!("Failed to load sources: {}", e))
+}
+
 #[tokio::main]
 async fn main() {
-    // Initialize tracing
+    let args = Args::parse();
+
+    match args.command.unwrap_or(Command::Serve) {
+        Command::Serve => serve(args.config).await,
+        Command::Validate(validate_args) => {
+            run_cli(args.config, &validate_args.paths, |sources| cli::validate(sources, &validate_args))
+        }
+        Command::List(list_args) => run_cli(args.config, &[], |sources| cli::list(sources, &list_args)),
+        Command::Show(show_args) => run_cli(args.config, &[], |sources| cli::show(sources, &show_args)),
+        Command::ExportSite(export_args) => {
+            run_cli(args.config, &[], |sources| cli::export_site(sources, &export_args))
+        }
+        Command::Report(report_args) => run_cli(args.config, &[], |sources| cli::report(sources, &report_args)),
+        Command::HashPassword => std::process::exit(cli::hash_password()),
+        Command::Config(config_command) => {
+            std::process::exit(cli::config(&resolve_config_path(args.config), &config_command))
+        }
+    }
+}
+
+/// Run a one-shot subcommand against the selected sources and exit with its code
+fn run_cli(config: Option<PathBuf>, paths: &[PathBuf], command: impl FnOnce(&[Source]) -> i32) -> ! {
+    // Keep stdout for the command's own output
     tracing_subscriber::registry()
-        .with(tracing_subscriber::fmt::layer())
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
         .init();
 
-    let args = Args::parse();
+    let code = match cli_sources(config, paths) {
+        Ok(sources) => command(&sources),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            cli::EXIT_USAGE
+        }
+    };
+    std::process::exit(code);
+}
 
-    // Determine config path
-    let config_path = args
-        .config
-        .or_else(|| env::var("OPENSPEC_UI_CONFIG").ok().map(PathBuf::from))
-        .unwrap_or_else(|| PathBuf::from("openspec-ui.json"));
+type FileWatcher = notify_debouncer_full::Debouncer<notify::RecommendedWatcher, FileIdMap>;
+
+/// Watch `path`, sending the changed paths accepted by `filter` to `paths_tx`.
+/// Plain reads are ignored.
+fn watch_path(
+    path: &std::path::Path,
+    mode: RecursiveMode,
+    filter: impl Fn(&std::path::Path) -> bool + Send + 'static,
+    paths_tx: mpsc::UnboundedSender<Vec<PathBuf>>,
+) -> Result<FileWatcher, notify::Error> {
+    // Using notify-debouncer-full to filter Access events
+    let mut debouncer = new_debouncer(
+        Duration::from_millis(500),
+        None, // No cache timeout
+        move |result: Result<Vec<DebouncedEvent>, Vec<notify::Error>>| match result {
+            Ok(events) => {
+                let mut paths = Vec::new();
+                for debounced_event in events {
+                    // Filter out Access events which are causing infinite loops
+                    if let EventKind::Access(_) = debounced_event.event.kind {
+                        continue;
+                    }
+                    if !debounced_event.event.paths.iter().any(|p| filter(p)) {
+                        continue;
+                    }
+                    tracing::info!("File changed: {:?} {:?}", debounced_event.event.paths, debounced_event.event.kind);
+                    paths.extend(debounced_event.event.paths);
+                }
+
+                if !paths.is_empty() {
+                    let _ = paths_tx.send(paths);
+                }
+            }
+            Err(errors) => {
+                for e in errors {
+                    tracing::warn!("File watcher error: {}", e);
+                }
+            }
+        },
+    )?;
+    debouncer.watcher().watch(path, mode)?;
+    Ok(debouncer)
+}
+
+async fn serve(config: Option<PathBuf>) {
+    // Initialize tracing. Dependency trace output is left out: notify traces every
+    // inotify event, which loops when the log file sits in a watched directory.
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_filter(LevelFilter::INFO))
+        .init();
+
+    let config_path = resolve_config_path(config);
 
     if !config_path.exists() {
         tracing::error!("Config file not found: {:?}", config_path);
@@ -502,6 +1386,14 @@ async fn main() {
         }
     };
 
+    let auth = match config_manager.load_auth().map_err(|e| e.to_string()).and_then(|auth| auth.map(Auth::new).transpose()) {
+        Ok(auth) => auth,
+        Err(e) => {
+            tracing::error!("Invalid auth config: {}", e);
+            std::process::exit(1);
+        }
+    };
+
     tracing::info!("Loaded {} sources", sources.len());
     for source in &sources {
         tracing::info!(
@@ -512,96 +1404,84 @@ async fn main() {
         );
     }
 
-    // Create broadcast channel for SSE updates
-    let (update_tx, _) = broadcast::channel::<()>(16);
-
     // Create a separate channel for watcher restarts (config changes)
     let (config_update_tx, _) = broadcast::channel::<()>(16);
     let config_update_tx_for_watcher = config_update_tx.clone();
 
     // Create app state
-    let state = AppState::new(sources.clone(), config_manager.clone(), update_tx.clone(), config_update_tx.clone());
+    let read_only = config_manager.load_read_only().unwrap_or(false);
+    if read_only {
+        tracing::info!("Read-only mode: all write endpoints are disabled");
+    }
+    let auth_enabled = auth.is_some();
+    let state = AppState::new(sources.clone(), read_only, auth, config_manager.clone(), config_update_tx.clone());
+    let auth_state = state.auth.clone();
+
+    // Changed paths from the file watcher are applied to the index before notifying clients
+    let (paths_tx, mut paths_rx) = mpsc::unbounded_channel::<Vec<PathBuf>>();
+    let state_for_index = state.clone();
+    tokio::spawn(async move {
+        while let Some(paths) = paths_rx.recv().await {
+            state_for_index.refresh_paths(&paths).await;
+        }
+    });
 
-    // Setup file watcher with dynamic update support
+    // One watcher per source, so a config change only restarts the watchers of sources that changed
     let state_for_watcher = state.clone();
     tokio::spawn(async move {
-        // Use full debouncer to get event kinds
-        let mut current_watcher: Option<notify_debouncer_full::Debouncer<notify::RecommendedWatcher, FileIdMap>> = None;
+        let mut watchers: HashMap<PathBuf, FileWatcher> = HashMap::new();
         let mut config_rx = config_update_tx_for_watcher.subscribe();
 
-        // Initial setup
-        let mut should_setup = true;
-
         loop {
-            if should_setup {
-                // Get current sources
-                let sources = state_for_watcher.get_sources().await;
+            let sources = state_for_watcher.get_sources().await;
+            let paths: HashSet<PathBuf> = sources.iter().filter(|s| s.valid).map(|s| s.path.clone()).collect();
 
-                // Drop old watcher if it exists
-                if let Some(debouncer) = current_watcher.take() {
-                    drop(debouncer);
+            watchers.retain(|path, _| {
+                let keep = paths.contains(path);
+                if !keep {
+                    tracing::info!("Stopped watching source: {:?}", path);
                 }
-
-                // Create new watcher
-                let update_tx_watcher = state_for_watcher.update_tx.clone();
-                
-                // Using notify-debouncer-full to filter Access events
-                match new_debouncer(
-                    Duration::from_millis(500),
-                    None, // No cache timeout
-                    move |result: Result<Vec<DebouncedEvent>, Vec<notify::Error>>| {
-                        match result {
-                            Ok(events) => {
-                                let mut changed = false;
-                                for debounced_event in events {
-                                    // Filter out Access events which are causing infinite loops
-                                    match debounced_event.event.kind {
-                                        EventKind::Access(_) => {
-                                            // Ignore access events
-                                            continue;
-                                        }
-                                        _ => {
-                                            tracing::info!("File changed: {:?} {:?}", debounced_event.event.paths, debounced_event.event.kind);
-                                            changed = true;
-                                            break;
-                                        }
-                                    }
-                                }
-                                
-                                if changed {
-                                    let _ = update_tx_watcher.send(());
-                                }
-                            }
-                            Err(errors) => {
-                                for e in errors {
-                                    tracing::warn!("File watcher error: {}", e);
-                                }
-                            }
-                        }
-                    },
-                ) {
-                    Ok(mut debouncer) => {
-                        // Watch all valid source directories
-                        for source in sources.iter().filter(|s| s.valid) {
-                            if let Err(e) = debouncer.watcher().watch(&source.path, RecursiveMode::Recursive) {
-                                tracing::warn!("Failed to watch source {:?}: {}", source.path, e);
-                            } else {
-                                tracing::info!("Watching source: {:?}", source.path);
-                            }
-                        }
-                        current_watcher = Some(debouncer);
-                    }
-                    Err(e) => {
-                        tracing::error!("Failed to create file watcher: {}", e);
+                keep
+            });
+            for path in paths {
+                if watchers.contains_key(&path) {
+                    continue;
+                }
+                match watch_path(&path, RecursiveMode::Recursive, |_| true, paths_tx.clone()) {
+                    Ok(watcher) => {
+                        tracing::info!("Watching source: {:?}", path);
+                        watchers.insert(path, watcher);
                     }
+                    Err(e) => tracing::warn!("Failed to watch source {:?}: {}", path, e),
                 }
-                should_setup = false;
             }
 
-            // Wait for config update signal
-            if config_rx.recv().await.is_ok() {
-                tracing::info!("File watcher: configuration updated, restarting watcher...");
-                should_setup = true;
+            // Wait for config update signal; after a lag the sources are simply re-synced
+            let _ = config_rx.recv().await;
+            tracing::info!("File watcher: configuration updated, syncing watchers...");
+        }
+    });
+
+    // Hand edits to the config file are applied without a restart. The directory is
+    // watched rather than the file so editors that save by replacing the file are seen.
+    let (config_tx, mut config_changes) = mpsc::unbounded_channel::<Vec<PathBuf>>();
+    let config_dir = match config_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let config_file_name = config_path.file_name().map(|name| name.to_os_string());
+    let is_config_file = move |path: &std::path::Path| path.file_name() == config_file_name.as_deref();
+    let config_watcher = watch_path(&config_dir, RecursiveMode::NonRecursive, is_config_file, config_tx)
+        .inspect_err(|e| tracing::warn!("Failed to watch config file {:?}: {}", config_path, e))
+        .ok();
+    let state_for_config = state.clone();
+    tokio::spawn(async move {
+        // Keep the watcher alive for as long as changes are handled
+        let _config_watcher = config_watcher;
+        while config_changes.recv().await.is_some() {
+            match reload_config(&state_for_config).await {
+                Ok(()) => tracing::info!("Config file reloaded"),
+                Err(e) => tracing::error!("Ignoring invalid config file, keeping the previous config: {}", e),
             }
         }
     });
@@ -667,12 +1547,24 @@ async fn main() {
         .route("/api/config", get(get_config))
         .route("/api/config/sources", put(update_sources))
         .route("/api/sources", get(get_sources))
-        .route("/api/changes", get(get_changes))
+        .route("/api/sources/{id}/diagnostics", get(get_source_diagnostics))
+        .route("/api/changes", get(get_changes).post(create_change))
         .route("/api/changes/{id}", get(get_change_detail))
+        .route("/api/changes/{id}/merged-specs", get(get_merged_specs))
+        .route("/api/changes/{id}/archive", post(archive_change))
+        .route("/api/changes/{id}/diagnostics", get(get_change_diagnostics))
+        .route("/api/changes/{id}/history", get(get_change_history))
+        .route("/api/changes/{id}/tasks/{task_id}", patch(update_task))
         .route("/api/specs", get(get_specs))
         .route("/api/specs/{id}", get(get_spec_detail))
+        .route("/api/specs/{id}/diagnostics", get(get_spec_diagnostics))
+        .route("/api/specs/{id}/versions", get(get_spec_versions))
+        .route("/api/specs/{id}/diff", get(get_spec_diff))
         .route("/api/ideas", get(get_ideas).post(create_idea))
+        .route("/api/search", get(search))
+        .route("/api/reports/status", get(get_status_report))
         .route("/api/ideas/{id}", delete(delete_idea).put(update_idea))
+        .route("/api/ideas/{id}/promote", post(promote_idea))
         .route("/api/events", get(sse_handler))
         .layer(cors)
         .with_state(state);
@@ -687,9 +1579,20 @@ async fn main() {
         app = app.fallback(static_handler);
     }
 
+    // Applied last so it also covers SSE and the frontend assets. The layer is
+    // always installed so auth added to the config later takes effect without a restart
+    app = app.layer(middleware::from_fn_with_state(auth_state, auth::require_auth));
+    if auth_enabled {
+        tracing::info!("Authentication enabled");
+    } else {
+        tracing::warn!("No auth configured; anyone who can reach the server can read and edit");
+    }
+
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
     tracing::info!("Starting server on http://localhost:{}", port);
 
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .unwrap();
 }
@@ -1,9 +1,14 @@
-use regex::Regex;
+use crate::history::GitInfo;
+use crate::paths::{self, PathError};
+use crate::spec::{self, DeltaSpec, MergedSpec, ParsedSpec};
+use crate::tasks::{self, TaskSection};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
 use walkdir::WalkDir;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct TaskStats {
     pub total: usize,
     pub done: usize,
@@ -19,7 +24,7 @@ pub enum ChangeStatus {
     Archived,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Change {
     pub id: String,
@@ -31,6 +36,8 @@ pub struct Change {
     pub has_tasks: bool,
     pub has_design: bool,
     pub task_stats: Option<TaskStats>,
+    /// Filled in from the source's git repository, when it has one
+    pub git: Option<GitInfo>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -43,7 +50,9 @@ pub struct ChangeDetail {
     pub proposal: Option<String>,
     pub design: Option<String>,
     pub specs: Vec<SpecContent>,
+    pub deltas: Vec<DeltaSpec>,
     pub tasks: Option<TasksContent>,
+    pub git: Option<GitInfo>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -56,14 +65,18 @@ pub struct SpecContent {
 pub struct TasksContent {
     pub raw: String,
     pub stats: TaskStats,
+    pub sections: Vec<TaskSection>,
+    /// Pass back when updating tasks.md to detect concurrent edits
+    pub version: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Spec {
     pub id: String,
     pub source_id: String,
     pub path: String,
+    pub git: Option<GitInfo>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -73,9 +86,10 @@ pub struct SpecDetail {
     pub source_id: String,
     pub path: String,
     pub content: String,
+    pub parsed: ParsedSpec,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Idea {
     pub id: String,
@@ -85,6 +99,8 @@ pub struct Idea {
     pub description: String,
     pub created_at: String,
     pub updated_at: String,
+    /// Id of the change this idea was promoted into
+    pub promoted_to: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -95,12 +111,14 @@ struct IdeaFrontmatter {
     project_id: Option<String>,
     created_at: String,
     updated_at: String,
+    #[serde(default)]
+    promoted_to: Option<String>,
 }
 
 fn parse_idea_frontmatter(content: &str) -> Option<IdeaFrontmatter> {
     let lines: Vec<&str> = content.lines().collect();
     
-    if !lines.get(0).map(|l| l.trim() == "---").unwrap_or(false) {
+    if !lines.first().map(|l| l.trim() == "---").unwrap_or(false) {
         return None;
     }
     
@@ -158,16 +176,13 @@ fn extract_idea_title_and_description(content: &str) -> (String, String) {
     (title, description)
 }
 
-/// Parse tasks.md content and count [x] vs [ ] checkboxes
+/// Parse tasks.md content and count checked vs unchecked checkboxes
 pub fn parse_task_stats(content: &str) -> TaskStats {
-    let done_re = Regex::new(r"- \[x\]").unwrap();
-    let todo_re = Regex::new(r"- \[ \]").unwrap();
-
-    let done = done_re.find_iter(content).count();
-    let todo = todo_re.find_iter(content).count();
+    let lines = tasks::task_lines(content);
+    let done = lines.iter().filter(|t| t.checked).count();
 
     TaskStats {
-        total: done + todo,
+        total: lines.len(),
         done,
     }
 }
@@ -187,8 +202,8 @@ fn compute_status(has_tasks: bool, task_stats: &Option<TaskStats>, is_archived:
     }
 }
 
-/// Scan a single change directory and return Change
-fn scan_change(change_path: &Path, source_id: &str, is_archived: bool) -> Option<Change> {
+/// Scan a single change directory of the source at `source_path` and return Change
+pub fn scan_change(source_path: &Path, change_path: &Path, source_id: &str, is_archived: bool) -> Option<Change> {
     let name = change_path.file_name()?.to_str()?;
 
     // Skip if not a directory
@@ -212,7 +227,7 @@ fn scan_change(change_path: &Path, source_id: &str, is_archived: bool) -> Option
     }
 
     let task_stats = if has_tasks {
-        std::fs::read_to_string(&tasks_path)
+        paths::read_to_string(source_path, &tasks_path)
             .ok()
             .map(|content| parse_task_stats(&content))
     } else {
@@ -231,6 +246,7 @@ fn scan_change(change_path: &Path, source_id: &str, is_archived: bool) -> Option
         has_tasks,
         has_design,
         task_stats,
+        git: None,
     })
 }
 
@@ -244,31 +260,27 @@ pub fn scan_changes(source_path: &Path, source_id: &str) -> Vec<Change> {
     }
 
     // Scan active changes
-    for entry in std::fs::read_dir(&changes_path).into_iter().flatten() {
-        if let Ok(entry) = entry {
-            let path = entry.path();
-            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    for entry in std::fs::read_dir(&changes_path).into_iter().flatten().flatten() {
+        let path = entry.path();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
 
-            // Skip archive directory
-            if name == "archive" {
-                continue;
-            }
+        // Skip archive directory
+        if name == "archive" {
+            continue;
+        }
 
-            if let Some(change) = scan_change(&path, source_id, false) {
-                changes.push(change);
-            }
+        if let Some(change) = scan_change(source_path, &path, source_id, false) {
+            changes.push(change);
         }
     }
 
     // Scan archived changes
     let archive_path = changes_path.join("archive");
     if archive_path.exists() {
-        for entry in std::fs::read_dir(&archive_path).into_iter().flatten() {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if let Some(change) = scan_change(&path, source_id, true) {
-                    changes.push(change);
-                }
+        for entry in std::fs::read_dir(&archive_path).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if let Some(change) = scan_change(source_path, &path, source_id, true) {
+                changes.push(change);
             }
         }
     }
@@ -276,46 +288,64 @@ pub fn scan_changes(source_path: &Path, source_id: &str) -> Vec<Change> {
     changes
 }
 
-/// Get full details for a specific change
-pub fn get_change_detail(source_path: &Path, source_id: &str, change_name: &str) -> Option<ChangeDetail> {
+/// Locate a change directory by name, returning its path and whether it is archived.
+/// Names that are not a single path component or lead outside the source are not found.
+pub fn find_change_dir(source_path: &Path, change_name: &str) -> Option<(PathBuf, bool)> {
     // Try active changes first
-    let mut change_path = source_path.join("changes").join(change_name);
-    let mut is_archived = false;
-
-    if !change_path.exists() {
-        // Try archive - need to search for name ending
-        let archive_path = source_path.join("changes").join("archive");
-        if archive_path.exists() {
-            for entry in std::fs::read_dir(&archive_path).into_iter().flatten() {
-                if let Ok(entry) = entry {
-                    let name = entry.file_name();
-                    let name_str = name.to_string_lossy();
-                    if name_str.ends_with(change_name) || name_str == change_name {
-                        change_path = entry.path();
-                        is_archived = true;
-                        break;
-                    }
-                }
+    let change_path = paths::resolve_entry(source_path, "changes", change_name, "").ok()?;
+    if change_path.exists() {
+        return Some((change_path, false));
+    }
+
+    // Try archive - either the full directory name or the name without its date prefix
+    let archive_path = source_path.join("changes").join("archive");
+    if archive_path.exists() {
+        for entry in std::fs::read_dir(&archive_path).into_iter().flatten().flatten() {
+            let name = entry.file_name();
+            let name_str = name.to_string_lossy();
+            if name_str == change_name || archived_change_name(&name_str) == change_name {
+                let path = entry.path();
+                return paths::confine(source_path, &path).ok().map(|_| (path, true));
             }
         }
     }
 
-    if !change_path.exists() {
-        return None;
+    None
+}
+
+/// Content version used as a write precondition (SHA-256 of the file content)
+pub fn content_version(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn tasks_content(raw: String) -> TasksContent {
+    let stats = parse_task_stats(&raw);
+    let sections = tasks::parse_tasks(&raw);
+    let version = content_version(&raw);
+    TasksContent {
+        raw,
+        stats,
+        sections,
+        version,
     }
+}
+
+/// Get full details for a specific change
+pub fn get_change_detail(source_path: &Path, source_id: &str, change_name: &str) -> Option<ChangeDetail> {
+    let (change_path, is_archived) = find_change_dir(source_path, change_name)?;
 
     let proposal_path = change_path.join("proposal.md");
     let tasks_path = change_path.join("tasks.md");
     let design_path = change_path.join("design.md");
     let specs_path = change_path.join("specs");
 
-    let proposal = std::fs::read_to_string(&proposal_path).ok();
-    let design = std::fs::read_to_string(&design_path).ok();
+    let proposal = paths::read_to_string(source_path, &proposal_path).ok();
+    let design = paths::read_to_string(source_path, &design_path).ok();
 
-    let tasks = std::fs::read_to_string(&tasks_path).ok().map(|raw| {
-        let stats = parse_task_stats(&raw);
-        TasksContent { raw, stats }
-    });
+    let tasks = paths::read_to_string(source_path, &tasks_path).ok().map(tasks_content);
 
     let has_tasks = tasks.is_some();
     let task_stats = tasks.as_ref().map(|t| t.stats.clone());
@@ -323,18 +353,16 @@ pub fn get_change_detail(source_path: &Path, source_id: &str, change_name: &str)
 
     // Scan specs within the change
     let mut specs = Vec::new();
+    let mut deltas = Vec::new();
     if specs_path.exists() {
-        for entry in WalkDir::new(&specs_path).min_depth(1) {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if path.is_file() && path.extension().map_or(false, |e| e == "md") {
-                    let relative = path.strip_prefix(&specs_path).unwrap_or(path);
-                    if let Ok(content) = std::fs::read_to_string(path) {
-                        specs.push(SpecContent {
-                            path: relative.display().to_string(),
-                            content,
-                        });
-                    }
+        for entry in WalkDir::new(&specs_path).min_depth(1).into_iter().flatten() {
+            let path = entry.path();
+            if path.is_file() && path.extension().is_some_and(|e| e == "md") {
+                let relative = path.strip_prefix(&specs_path).unwrap_or(path);
+                if let Ok(content) = paths::read_to_string(source_path, path) {
+                    let path = relative.display().to_string();
+                    deltas.push(spec::parse_delta_spec(&path, &content));
+                    specs.push(SpecContent { path, content });
                 }
             }
         }
@@ -350,49 +378,488 @@ pub fn get_change_detail(source_path: &Path, source_id: &str, change_name: &str)
         proposal,
         design,
         specs,
+        deltas,
         tasks,
+        git: None,
     })
 }
 
+#[derive(Debug, Error)]
+pub enum TaskUpdateError {
+    #[error("Change not found")]
+    ChangeNotFound,
+    #[error("Change has no tasks.md")]
+    TasksNotFound,
+    #[error("Task '{0}' not found")]
+    TaskNotFound(String),
+    #[error("tasks.md was modified since it was read")]
+    VersionMismatch,
+    #[error("Tasks of an archived change cannot be edited")]
+    ChangeArchived,
+    #[error(transparent)]
+    InvalidPath(#[from] PathError),
+    #[error("Failed to write tasks.md: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Set or flip a single task checkbox in tasks.md, leaving every other byte untouched.
+/// `version` must match the current content version, otherwise nothing is written.
+/// Callers that can run concurrently must hold the file's lock (see `AppState::lock_file`)
+/// so the version check and the write happen as one step.
+pub fn update_task(
+    source_path: &Path,
+    change_name: &str,
+    task_id: &str,
+    version: &str,
+    checked: Option<bool>,
+) -> Result<TasksContent, TaskUpdateError> {
+    let (change_path, archived) = find_change_dir(source_path, change_name).ok_or(TaskUpdateError::ChangeNotFound)?;
+    if archived {
+        return Err(TaskUpdateError::ChangeArchived);
+    }
+    let tasks_path = change_path.join("tasks.md");
+    paths::confine(source_path, &tasks_path)?;
+    let raw = std::fs::read_to_string(&tasks_path).map_err(|_| TaskUpdateError::TasksNotFound)?;
+
+    if content_version(&raw) != version {
+        return Err(TaskUpdateError::VersionMismatch);
+    }
+
+    let updated = tasks::set_task_checked(&raw, task_id, checked)
+        .ok_or_else(|| TaskUpdateError::TaskNotFound(task_id.to_string()))?;
+    std::fs::write(&tasks_path, &updated)?;
+
+    Ok(tasks_content(updated))
+}
+
+#[derive(Debug, Error)]
+pub enum CreateChangeError {
+    #[error("Invalid name '{0}': use kebab-case such as add-user-auth, other than 'archive'")]
+    InvalidName(String),
+    #[error("Change '{0}' already exists")]
+    AlreadyExists(String),
+    #[error(transparent)]
+    InvalidPath(#[from] PathError),
+    #[error("Failed to write change: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Initial content for a new change directory
+pub struct NewChange<'a> {
+    pub name: &'a str,
+    pub proposal: Option<&'a str>,
+    pub design: bool,
+    pub capability: Option<&'a str>,
+    /// Idea the change was promoted from, recorded in the proposal's Impact section
+    pub origin_idea: Option<&'a str>,
+}
+
+fn is_kebab_case(name: &str) -> bool {
+    !name.is_empty()
+        && name.split('-').all(|part| {
+            !part.is_empty() && part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        })
+}
+
+/// Change name of an archive directory, without its `YYYY-MM-DD-` prefix
+pub fn archived_change_name(dir_name: &str) -> &str {
+    let bytes = dir_name.as_bytes();
+    let dated = bytes.len() > 11
+        && bytes[..10]
+            .iter()
+            .enumerate()
+            .all(|(i, b)| if i == 4 || i == 7 { *b == b'-' } else { b.is_ascii_digit() })
+        && bytes[10] == b'-';
+    if dated {
+        &dir_name[11..]
+    } else {
+        dir_name
+    }
+}
+
+/// `add-user-auth` -> `Add User Auth`
+fn title_case(name: &str) -> String {
+    name.split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Scaffold `changes/<name>/` with proposal.md, an empty tasks.md and optionally
+/// design.md and a delta spec skeleton. Names already used by an active or
+/// archived change are rejected.
+pub fn create_change(source_path: &Path, source_id: &str, new: &NewChange) -> Result<Change, CreateChangeError> {
+    // `changes/archive` holds archived changes, so it can never be a change itself
+    if !is_kebab_case(new.name) || new.name == "archive" {
+        return Err(CreateChangeError::InvalidName(new.name.to_string()));
+    }
+    if let Some(capability) = new.capability.filter(|c| !is_kebab_case(c)) {
+        return Err(CreateChangeError::InvalidName(capability.to_string()));
+    }
+
+    let changes_path = source_path.join("changes");
+    let archived = std::fs::read_dir(changes_path.join("archive"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .any(|entry| archived_change_name(&entry.file_name().to_string_lossy()) == new.name);
+    if archived {
+        return Err(CreateChangeError::AlreadyExists(new.name.to_string()));
+    }
+
+    let change_path = paths::resolve_entry(source_path, "changes", new.name, "")?;
+    std::fs::create_dir_all(&changes_path)?;
+    // create_dir fails if the directory exists, which also covers concurrent requests
+    std::fs::create_dir(&change_path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::AlreadyExists => CreateChangeError::AlreadyExists(new.name.to_string()),
+        _ => CreateChangeError::Io(e),
+    })?;
+
+    // Don't leave a half-written change behind for the board to pick up
+    if let Err(e) = write_change_files(&change_path, new) {
+        let _ = std::fs::remove_dir_all(&change_path);
+        return Err(e.into());
+    }
+
+    scan_change(source_path, &change_path, source_id, false)
+        .ok_or_else(|| CreateChangeError::Io(std::io::Error::other("Failed to read back new change")))
+}
+
+fn write_change_files(change_path: &Path, new: &NewChange) -> std::io::Result<()> {
+    let title = title_case(new.name);
+    let why = new.proposal.map(str::trim).filter(|p| !p.is_empty()).unwrap_or("TBD");
+    let affected_specs = new.capability.map_or("TBD".to_string(), |c| format!("`{}`", c));
+    let origin = new.origin_idea.map_or(String::new(), |id| format!("- Origin idea: `{}`\n", id));
+    std::fs::write(
+        change_path.join("proposal.md"),
+        format!(
+            "# Change: {}\n\n## Why\n{}\n\n## What Changes\n- TBD\n\n## Impact\n- Affected specs: {}\n- Affected code: TBD\n{}",
+            title, why, affected_specs, origin
+        ),
+    )?;
+    std::fs::write(change_path.join("tasks.md"), format!("# Tasks: {}\n", title))?;
+
+    if new.design {
+        std::fs::write(
+            change_path.join("design.md"),
+            format!(
+                "# Design: {}\n\n## Context\nTBD\n\n## Goals / Non-Goals\n\n## Decisions\n\n## Risks / Trade-offs\n",
+                title
+            ),
+        )?;
+    }
+
+    if let Some(capability) = new.capability {
+        let spec_dir = change_path.join("specs").join(capability);
+        std::fs::create_dir_all(&spec_dir)?;
+        std::fs::write(
+            spec_dir.join("spec.md"),
+            "## ADDED Requirements\n\n### Requirement: TBD\nThe system SHALL TBD.\n\n#### Scenario: TBD\n- **WHEN** TBD\n- **THEN** TBD\n",
+        )?;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Error)]
+pub enum PromoteIdeaError {
+    #[error("Idea not found")]
+    IdeaNotFound,
+    #[error("Idea was already promoted to '{0}'")]
+    AlreadyPromoted(String),
+    #[error(transparent)]
+    Create(#[from] CreateChangeError),
+    #[error(transparent)]
+    InvalidPath(#[from] PathError),
+    #[error("Failed to update idea: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Kebab-case change name derived from an idea title, e.g. `Add SSE reconnect!` -> `add-sse-reconnect`
+pub fn slugify(title: &str) -> String {
+    let words: Vec<String> = title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_ascii_lowercase())
+        .collect();
+
+    let mut slug = String::new();
+    for word in words {
+        if !slug.is_empty() && slug.len() + word.len() + 1 > 50 {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word);
+    }
+    slug
+}
+
+/// Record in the idea's frontmatter which change it was promoted into
+fn mark_idea_promoted(idea_path: &Path, change_id: &str) -> std::io::Result<()> {
+    let content = std::fs::read_to_string(idea_path)?;
+    // Insert before the closing `---` of the frontmatter, which starts at the second line
+    let close = content
+        .match_indices("\n---")
+        .map(|(i, _)| i + 1)
+        .next()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid idea file format"))?;
+
+    let mut updated = content;
+    updated.insert_str(close, &format!("promotedTo: {}\n", change_id));
+    std::fs::write(idea_path, updated)
+}
+
+/// Create a change seeded from an idea's title and description.
+/// The idea is deleted when `delete_idea` is set, otherwise it is marked with `promotedTo`.
+/// Returns the new change and the updated idea (None when deleted).
+pub fn promote_idea(
+    source_path: &Path,
+    source_id: &str,
+    idea_id: &str,
+    name: Option<&str>,
+    delete_idea: bool,
+) -> Result<(Change, Option<Idea>), PromoteIdeaError> {
+    let idea_path = paths::resolve_entry(source_path, "ideas", idea_id, ".md")?;
+    let idea = read_idea(&idea_path, source_id).ok_or(PromoteIdeaError::IdeaNotFound)?;
+    if let Some(change_id) = idea.promoted_to {
+        return Err(PromoteIdeaError::AlreadyPromoted(change_id));
+    }
+
+    let slug = slugify(&idea.title);
+    let name = match name {
+        Some(name) => name,
+        None if slug.is_empty() => idea_id,
+        None => &slug,
+    };
+    let proposal = if idea.description.trim().is_empty() { &idea.title } else { &idea.description };
+    let change = create_change(
+        source_path,
+        source_id,
+        &NewChange {
+            name,
+            proposal: Some(proposal),
+            design: false,
+            capability: None,
+            origin_idea: Some(&idea.id),
+        },
+    )?;
+
+    if delete_idea {
+        std::fs::remove_file(&idea_path)?;
+        return Ok((change, None));
+    }
+    mark_idea_promoted(&idea_path, &change.id)?;
+    Ok((change, read_idea(&idea_path, source_id)))
+}
+
+/// Preview the source-of-truth specs a change would produce once archived
+pub fn get_merged_specs(source_path: &Path, source_id: &str, change_name: &str) -> Option<Vec<MergedSpec>> {
+    let detail = get_change_detail(source_path, source_id, change_name)?;
+
+    let merged = detail
+        .deltas
+        .iter()
+        .map(|delta| {
+            let base = paths::resolve(source_path, &format!("specs/{}", delta.path))
+                .ok()
+                .and_then(|path| std::fs::read_to_string(path).ok());
+            spec::merge_spec(base.as_deref(), delta, change_name)
+        })
+        .collect();
+
+    Some(merged)
+}
+
+#[derive(Debug, Error)]
+pub enum ArchiveError {
+    #[error("Change not found")]
+    ChangeNotFound,
+    #[error("Change is already archived")]
+    AlreadyArchived,
+    #[error("{done} of {total} tasks are done; archive with force to override")]
+    IncompleteTasks { done: usize, total: usize },
+    #[error("Delta specs do not apply cleanly: {}", .0.join("; "))]
+    Conflicts(Vec<String>),
+    #[error("Archive directory '{0}' already exists")]
+    ArchiveExists(String),
+    #[error(transparent)]
+    InvalidPath(#[from] PathError),
+    #[error("Failed to archive change: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchivedChange {
+    pub change: Change,
+    /// Source-of-truth specs as written, with the requirement-level changes applied
+    pub specs: Vec<MergedSpec>,
+}
+
+/// A merged spec written next to its target, waiting to be moved into place
+struct StagedSpec {
+    target: PathBuf,
+    staged: PathBuf,
+    /// Content of the target before archiving, None for a new capability
+    original: Option<Vec<u8>>,
+    /// Outermost directory created to hold the target
+    created_dir: Option<PathBuf>,
+}
+
+impl StagedSpec {
+    fn write(target: PathBuf, content: &str, staged: &mut Vec<StagedSpec>) -> std::io::Result<()> {
+        let parent = target.parent().unwrap_or(Path::new("")).to_path_buf();
+        let name = target.file_name().unwrap_or_default().to_string_lossy();
+        let spec = StagedSpec {
+            staged: target.with_file_name(format!(".{}.archive", name)),
+            original: std::fs::read(&target).ok(),
+            created_dir: parent.ancestors().take_while(|a| !a.exists()).last().map(Path::to_path_buf),
+            target,
+        };
+        let staged_path = spec.staged.clone();
+        // Recorded before writing so a rollback also removes a half-created directory
+        staged.push(spec);
+
+        std::fs::create_dir_all(&parent)?;
+        std::fs::write(staged_path, content)
+    }
+
+    /// Remove staged files and restore the first `committed` targets, which were already replaced
+    fn rollback(staged: &[StagedSpec], committed: usize) {
+        for (i, spec) in staged.iter().enumerate() {
+            if i < committed {
+                let _ = match &spec.original {
+                    Some(original) => std::fs::write(&spec.target, original),
+                    None => std::fs::remove_file(&spec.target),
+                };
+            }
+            let _ = std::fs::remove_file(&spec.staged);
+            if let Some(dir) = &spec.created_dir {
+                let _ = std::fs::remove_dir_all(dir);
+            }
+        }
+    }
+}
+
+/// Archive a change the way `openspec archive` does: apply its delta specs to `specs/`
+/// and move it to `changes/archive/<today>-<name>`. Refuses when a delta does not apply
+/// cleanly, and when tasks are unfinished unless `force` is set.
+pub fn archive_change(
+    source_path: &Path,
+    source_id: &str,
+    change_name: &str,
+    force: bool,
+) -> Result<ArchivedChange, ArchiveError> {
+    let change_path = paths::resolve_entry(source_path, "changes", change_name, "")?;
+    if change_name == "archive" || !change_path.join("proposal.md").is_file() {
+        return Err(match find_change_dir(source_path, change_name) {
+            Some((_, true)) => ArchiveError::AlreadyArchived,
+            _ => ArchiveError::ChangeNotFound,
+        });
+    }
+
+    if let Ok(raw) = std::fs::read_to_string(change_path.join("tasks.md")) {
+        let stats = parse_task_stats(&raw);
+        if stats.done < stats.total && !force {
+            return Err(ArchiveError::IncompleteTasks {
+                done: stats.done,
+                total: stats.total,
+            });
+        }
+    }
+
+    let merged = get_merged_specs(source_path, source_id, change_name).ok_or(ArchiveError::ChangeNotFound)?;
+    let conflicts: Vec<String> = merged
+        .iter()
+        .flat_map(|m| m.conflicts.iter().map(move |c| format!("{}: {}", m.capability, c)))
+        .collect();
+    if !conflicts.is_empty() {
+        return Err(ArchiveError::Conflicts(conflicts));
+    }
+
+    let archive_name = format!("{}-{}", chrono::Local::now().format("%Y-%m-%d"), change_name);
+    let archive_path = source_path.join("changes").join("archive").join(&archive_name);
+    if archive_path.exists() {
+        return Err(ArchiveError::ArchiveExists(archive_name));
+    }
+
+    // Resolve every target before writing so a bad path leaves the source untouched
+    let spec_paths = merged
+        .iter()
+        .map(|spec| paths::resolve(source_path, &format!("specs/{}", spec.path)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Write the merged specs next to their targets, then move the change, then swap the
+    // specs in; a failure at any step undoes the earlier ones
+    let mut staged = Vec::new();
+    for (spec, spec_path) in merged.iter().zip(spec_paths) {
+        if let Err(e) = StagedSpec::write(spec_path, &spec.content, &mut staged) {
+            StagedSpec::rollback(&staged, 0);
+            return Err(e.into());
+        }
+    }
+    let moved = std::fs::create_dir_all(source_path.join("changes").join("archive"))
+        .and_then(|_| std::fs::rename(&change_path, &archive_path));
+    if let Err(e) = moved {
+        StagedSpec::rollback(&staged, 0);
+        return Err(e.into());
+    }
+    for (i, spec) in staged.iter().enumerate() {
+        if let Err(e) = std::fs::rename(&spec.staged, &spec.target) {
+            StagedSpec::rollback(&staged, i);
+            let _ = std::fs::rename(&archive_path, &change_path);
+            return Err(e.into());
+        }
+    }
+
+    let change = scan_change(source_path, &archive_path, source_id, true).ok_or(ArchiveError::ChangeNotFound)?;
+    Ok(ArchivedChange { change, specs: merged })
+}
+
 /// Scan specs/ directory for source-of-truth specs
 pub fn scan_specs(source_path: &Path, source_id: &str) -> Vec<Spec> {
     let mut specs = Vec::new();
     let specs_path = source_path.join("specs");
 
     // Include root-level markdown files
-    for entry in std::fs::read_dir(source_path).into_iter().flatten() {
-        if let Ok(entry) = entry {
-            let path = entry.path();
-            if path.is_file() && path.extension().map_or(false, |e| e == "md") {
-                let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-                // Skip common change files and changes directory
-                if name != "proposal.md" && name != "tasks.md" && name != "design.md" && name != "changes" {
-                    let id = format!("{}/{}", source_id, name.replace(".md", ""));
-                    specs.push(Spec {
-                        id,
-                        source_id: source_id.to_string(),
-                        path: name.to_string(),
-                    });
-                }
+    for entry in std::fs::read_dir(source_path).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.is_file() && path.extension().is_some_and(|e| e == "md") {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            // Skip common change files and changes directory
+            if name != "proposal.md" && name != "tasks.md" && name != "design.md" && name != "changes" {
+                let id = format!("{}/{}", source_id, name.replace(".md", ""));
+                specs.push(Spec {
+                    id,
+                    source_id: source_id.to_string(),
+                    path: name.to_string(),
+                    git: None,
+                });
             }
         }
     }
 
     // Scan specs/ directory
     if specs_path.exists() {
-        for entry in WalkDir::new(&specs_path).min_depth(1) {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if path.is_file() && path.extension().map_or(false, |e| e == "md") {
-                    let relative = path.strip_prefix(&specs_path).unwrap_or(path);
-                    let path_str = relative.display().to_string();
-                    let id = format!("{}/{}", source_id, path_str.replace("/spec.md", "").replace(".md", ""));
-                    specs.push(Spec {
-                        id,
-                        source_id: source_id.to_string(),
-                        path: path_str,
-                    });
-                }
+        for entry in WalkDir::new(&specs_path).min_depth(1).into_iter().flatten() {
+            let path = entry.path();
+            if path.is_file() && path.extension().is_some_and(|e| e == "md") {
+                let relative = path.strip_prefix(&specs_path).unwrap_or(path);
+                let path_str = relative.display().to_string();
+                let id = format!("{}/{}", source_id, path_str.replace("/spec.md", "").replace(".md", ""));
+                specs.push(Spec {
+                    id,
+                    source_id: source_id.to_string(),
+                    path: path_str,
+                    git: None,
+                });
             }
         }
     }
@@ -400,27 +867,47 @@ pub fn scan_specs(source_path: &Path, source_id: &str) -> Vec<Spec> {
     specs
 }
 
+/// File of a spec given its `Spec::path`: a root-level file if one exists, otherwise under specs/.
+/// Paths leading outside the source resolve to nothing.
+pub fn resolve_spec(source_path: &Path, spec_path: &str) -> Option<PathBuf> {
+    [spec_path.to_string(), format!("specs/{}", spec_path)]
+        .iter()
+        .filter_map(|relative| paths::resolve(source_path, relative).ok())
+        .find(|path| path.is_file())
+}
+
 /// Get content for a specific spec
 pub fn get_spec_detail(source_path: &Path, source_id: &str, spec_path: &str) -> Option<SpecDetail> {
-    // First try root-level file
-    let mut full_path = source_path.join(spec_path);
-
-    if !full_path.exists() {
-        // Then try specs/ directory
-        full_path = source_path.join("specs").join(spec_path);
-        if !full_path.exists() {
-            return None;
-        }
-    }
+    let full_path = resolve_spec(source_path, spec_path)?;
 
     let content = std::fs::read_to_string(&full_path).ok()?;
     let id = format!("{}/{}", source_id, spec_path.replace("/spec.md", "").replace(".md", ""));
+    let parsed = spec::parse_spec(&content);
 
     Some(SpecDetail {
         id,
         source_id: source_id.to_string(),
         path: spec_path.to_string(),
         content,
+        parsed,
+    })
+}
+
+/// Read a single idea file, returning None if it lacks valid frontmatter
+pub fn read_idea(path: &Path, source_id: &str) -> Option<Idea> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let frontmatter = parse_idea_frontmatter(&content)?;
+    let (title, description) = extract_idea_title_and_description(&content);
+
+    Some(Idea {
+        id: format!("{}/{}", source_id, frontmatter.id),
+        source_id: source_id.to_string(),
+        project_id: frontmatter.project_id,
+        title,
+        description,
+        created_at: frontmatter.created_at,
+        updated_at: frontmatter.updated_at,
+        promoted_to: frontmatter.promoted_to,
     })
 }
 
@@ -433,26 +920,12 @@ pub fn scan_ideas(source_path: &Path, source_id: &str) -> Vec<Idea> {
         return ideas;
     }
 
-    for entry in std::fs::read_dir(&ideas_path).into_iter().flatten() {
-        if let Ok(entry) = entry {
-            let path = entry.path();
-            
-            if path.is_file() && path.extension().map_or(false, |e| e == "md") {
-                if let Some(content) = std::fs::read_to_string(&path).ok() {
-                    if let Some(frontmatter) = parse_idea_frontmatter(&content) {
-                        let (title, description) = extract_idea_title_and_description(&content);
-                        ideas.push(Idea {
-                            id: format!("{}/{}", source_id, frontmatter.id),
-                            source_id: source_id.to_string(),
-                            project_id: frontmatter.project_id,
-                            title,
-                            description,
-                            created_at: frontmatter.created_at,
-                            updated_at: frontmatter.updated_at,
-                        });
-                    }
-                }
-            }
+    for entry in std::fs::read_dir(&ideas_path).into_iter().flatten().flatten() {
+        let path = entry.path();
+
+        let is_markdown = path.is_file() && path.extension().is_some_and(|e| e == "md");
+        if is_markdown && paths::confine(source_path, &path).is_ok() {
+            ideas.extend(read_idea(&path, source_id));
         }
     }
 
@@ -491,7 +964,7 @@ updatedAt: {}
         id, project_id_line, now, now, title, description
     );
 
-    let idea_path = ideas_path.join(format!("{}.md", id));
+    let idea_path = paths::resolve_entry(source_path, "ideas", id, ".md")?;
     std::fs::write(&idea_path, content)?;
 
     Ok(Idea {
@@ -502,12 +975,13 @@ updatedAt: {}
         description: description.to_string(),
         created_at: now.clone(),
         updated_at: now,
+        promoted_to: None,
     })
 }
 
 /// Delete idea from file system
 pub fn delete_idea(source_path: &Path, id: &str) -> std::io::Result<()> {
-    let idea_path = source_path.join("ideas").join(format!("{}.md", id));
+    let idea_path = paths::resolve_entry(source_path, "ideas", id, ".md")?;
 
     if idea_path.exists() {
         std::fs::remove_file(idea_path)?;
@@ -518,7 +992,7 @@ pub fn delete_idea(source_path: &Path, id: &str) -> std::io::Result<()> {
 
 /// Update idea in file system
 pub fn update_idea(source_path: &Path, source_id: &str, id: &str, title: &str, description: &str) -> std::io::Result<Idea> {
-    let idea_path = source_path.join("ideas").join(format!("{}.md", id));
+    let idea_path = paths::resolve_entry(source_path, "ideas", id, ".md")?;
 
     if !idea_path.exists() {
         return Err(std::io::Error::new(
@@ -543,19 +1017,25 @@ pub fn update_idea(source_path: &Path, source_id: &str, id: &str, title: &str, d
         String::new()
     };
 
+    let promoted_to_line = if let Some(ref change_id) = frontmatter.promoted_to {
+        format!("\npromotedTo: {}", change_id)
+    } else {
+        String::new()
+    };
+
     let content = format!(
         r#"---
 id: {}
 {}
 createdAt: {}
-updatedAt: {}
+updatedAt: {}{}
 ---
 
 # {}
 
 {}
 "#,
-        id, project_id_line, frontmatter.created_at, now, title, description
+        id, project_id_line, frontmatter.created_at, now, promoted_to_line, title, description
     );
 
     std::fs::write(&idea_path, content)?;
@@ -568,12 +1048,14 @@ updatedAt: {}
         description: description.to_string(),
         created_at: frontmatter.created_at,
         updated_at: now,
+        promoted_to: frontmatter.promoted_to,
     })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::TempDir;
 
     #[test]
     fn test_parse_idea_frontmatter_with_empty_line() {
@@ -648,4 +1130,191 @@ Description"#;
             ChangeStatus::Archived
         );
     }
+
+    #[test]
+    fn test_create_change_scaffold() {
+        let root = TempDir::new("create");
+        std::fs::create_dir_all(root.join("changes/archive/2026-01-01-add-foo")).unwrap();
+
+        let new = NewChange {
+            name: "add-bar",
+            proposal: Some("Users need bars."),
+            design: true,
+            capability: Some("bar"),
+            origin_idea: None,
+        };
+        let change = create_change(&root, "test", &new).unwrap();
+        assert_eq!(change.id, "test/add-bar");
+        assert!(change.has_design && change.has_specs && change.has_tasks);
+        let proposal = std::fs::read_to_string(root.join("changes/add-bar/proposal.md")).unwrap();
+        assert!(proposal.starts_with("# Change: Add Bar\n\n## Why\nUsers need bars.\n"));
+        let delta = std::fs::read_to_string(root.join("changes/add-bar/specs/bar/spec.md")).unwrap();
+        assert_eq!(spec::parse_delta_spec("bar/spec.md", &delta).added.len(), 1);
+
+        assert!(matches!(create_change(&root, "test", &new), Err(CreateChangeError::AlreadyExists(_))));
+        let archived = NewChange { name: "add-foo", proposal: None, design: false, capability: None, origin_idea: None };
+        assert!(matches!(create_change(&root, "test", &archived), Err(CreateChangeError::AlreadyExists(_))));
+        let foo = NewChange { name: "foo", ..archived };
+        assert!(create_change(&root, "test", &foo).is_ok());
+        for name in ["Add-Foo", "add--foo", "-add", "add/foo", "../x", "", "archive"] {
+            let bad = NewChange { name, proposal: None, design: false, capability: None, origin_idea: None };
+            assert!(matches!(create_change(&root, "test", &bad), Err(CreateChangeError::InvalidName(_))));
+        }
+
+        // A capability name too long for the filesystem fails after the change directory exists
+        let capability = "a".repeat(300);
+        let broken = NewChange { name: "add-baz", capability: Some(&capability), ..new };
+        assert!(matches!(create_change(&root, "test", &broken), Err(CreateChangeError::Io(_))));
+        assert!(!root.join("changes/add-baz").exists());
+    }
+
+    #[test]
+    fn test_promote_idea() {
+        let root = TempDir::new("promote");
+        save_idea(&root, "test", "idea-1", "Add SSE reconnect!", "Clients lose events.", None).unwrap();
+        save_idea(&root, "test", "idea-2", "Other", "Gone soon.", None).unwrap();
+
+        let (change, idea) = promote_idea(&root, "test", "idea-1", None, false).unwrap();
+        assert_eq!(change.id, "test/add-sse-reconnect");
+        let proposal = std::fs::read_to_string(root.join("changes/add-sse-reconnect/proposal.md")).unwrap();
+        assert!(proposal.contains("Clients lose events."));
+        assert!(proposal.contains("- Origin idea: `test/idea-1`"));
+        assert_eq!(idea.unwrap().promoted_to.as_deref(), Some("test/add-sse-reconnect"));
+
+        // Editing keeps the link
+        let edited = update_idea(&root, "test", "idea-1", "Add SSE reconnect", "Edited.").unwrap();
+        assert_eq!(edited.promoted_to.as_deref(), Some("test/add-sse-reconnect"));
+        assert!(matches!(
+            promote_idea(&root, "test", "idea-1", None, false),
+            Err(PromoteIdeaError::AlreadyPromoted(_))
+        ));
+
+        let (change, idea) = promote_idea(&root, "test", "idea-2", Some("add-other"), true).unwrap();
+        assert_eq!(change.name, "add-other");
+        assert!(idea.is_none());
+        assert!(!root.join("ideas/idea-2.md").exists());
+        assert!(matches!(promote_idea(&root, "test", "idea-2", None, false), Err(PromoteIdeaError::IdeaNotFound)));
+    }
+
+    #[test]
+    fn test_archive_change() {
+        let root = TempDir::new("archive");
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write("specs/chat/spec.md", "# Chat\n\n## Requirements\n\n### Requirement: Send\nThe system SHALL send.\n");
+        write("changes/add-edit/proposal.md", "# Change: Add Edit\n");
+        write("changes/add-edit/tasks.md", "- [x] 1.1 Done\n- [ ] 1.2 Todo\n");
+        write(
+            "changes/add-edit/specs/chat/spec.md",
+            "## ADDED Requirements\n\n### Requirement: Edit\nThe system SHALL edit.\n",
+        );
+
+        assert!(matches!(
+            archive_change(&root, "test", "add-edit", false),
+            Err(ArchiveError::IncompleteTasks { done: 1, total: 2 })
+        ));
+        assert!(matches!(archive_change(&root, "test", "missing", false), Err(ArchiveError::ChangeNotFound)));
+
+        write("changes/bad-edit/proposal.md", "# Change: Bad Edit\n");
+        write(
+            "changes/bad-edit/specs/chat/spec.md",
+            "## MODIFIED Requirements\n\n### Requirement: Missing\nThe system SHALL fail.\n",
+        );
+        assert!(matches!(
+            archive_change(&root, "test", "bad-edit", true),
+            Err(ArchiveError::Conflicts(conflicts)) if conflicts == ["chat: MODIFIED requirement 'Missing' not found"]
+        ));
+        assert!(root.join("changes/bad-edit/proposal.md").exists());
+
+        // A target that cannot be replaced leaves both the specs and the change as they were
+        std::fs::create_dir_all(root.join("specs/broken/spec.md")).unwrap();
+        write(
+            "changes/add-edit/specs/broken/spec.md",
+            "## ADDED Requirements\n\n### Requirement: Broken\nThe system SHALL break.\n",
+        );
+        let chat = std::fs::read_to_string(root.join("specs/chat/spec.md")).unwrap();
+        assert!(matches!(archive_change(&root, "test", "add-edit", true), Err(ArchiveError::Io(_))));
+        assert_eq!(std::fs::read_to_string(root.join("specs/chat/spec.md")).unwrap(), chat);
+        assert!(!root.join("specs/chat/.spec.md.archive").exists());
+        assert!(!root.join("specs/broken/.spec.md.archive").exists());
+        assert!(root.join("changes/add-edit/proposal.md").exists());
+        assert!(std::fs::read_dir(root.join("changes/archive")).unwrap().next().is_none());
+        std::fs::remove_dir_all(root.join("changes/add-edit/specs/broken")).unwrap();
+
+        let archived = archive_change(&root, "test", "add-edit", true).unwrap();
+        assert_eq!(archived.change.status, ChangeStatus::Archived);
+        assert_eq!(archived.specs[0].requirements[0].name, "Edit");
+        assert!(!root.join("changes/add-edit").exists());
+        assert!(root.join("changes/archive").join(&archived.change.name).join("proposal.md").exists());
+        let spec = std::fs::read_to_string(root.join("specs/chat/spec.md")).unwrap();
+        assert!(spec.contains("### Requirement: Send") && spec.contains("### Requirement: Edit"));
+
+        assert!(matches!(archive_change(&root, "test", "add-edit", true), Err(ArchiveError::AlreadyArchived)));
+        let version = content_version("- [x] 1.1 Done\n- [ ] 1.2 Todo\n");
+        assert!(matches!(
+            update_task(&root, "add-edit", "1.2", &version, Some(true)),
+            Err(TaskUpdateError::ChangeArchived)
+        ));
+    }
+
+    #[test]
+    fn test_ids_cannot_escape_source() {
+        let root = TempDir::new("traversal");
+        let source = root.join("openspec");
+        std::fs::create_dir_all(source.join("ideas")).unwrap();
+        std::fs::create_dir_all(source.join("changes/add-edit")).unwrap();
+        std::fs::write(source.join("changes/add-edit/proposal.md"), "# Change: Add Edit\n").unwrap();
+        std::fs::write(root.join("victim.md"), "---\nid: victim\n---\n\n# Victim\n").unwrap();
+        std::fs::write(root.join("tasks.md"), "- [ ] 1.1 Outside\n").unwrap();
+
+        let kind = |result: std::io::Result<()>| result.unwrap_err().kind();
+        assert_eq!(kind(delete_idea(&source, "../../victim")), std::io::ErrorKind::InvalidInput);
+        assert_eq!(
+            kind(update_idea(&source, "test", "../../victim", "Owned", "").map(|_| ())),
+            std::io::ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            kind(save_idea(&source, "test", "../victim", "Owned", "", None).map(|_| ())),
+            std::io::ErrorKind::InvalidInput
+        );
+        assert!(matches!(
+            promote_idea(&source, "test", "../../victim", None, true),
+            Err(PromoteIdeaError::InvalidPath(_))
+        ));
+        assert!(std::fs::read_to_string(root.join("victim.md")).unwrap().contains("# Victim"));
+
+        assert!(get_spec_detail(&source, "test", "../victim.md").is_none());
+        assert!(get_spec_detail(&source, "test", "/etc/hostname").is_none());
+        assert!(get_change_detail(&source, "test", "..").is_none());
+        assert!(find_change_dir(&source, "../..").is_none());
+        assert!(matches!(archive_change(&source, "test", "../..", true), Err(ArchiveError::InvalidPath(_))));
+        assert!(matches!(
+            update_task(&source, "..", "1.1", "", Some(true)),
+            Err(TaskUpdateError::ChangeNotFound)
+        ));
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(root.join("victim.md"), source.join("ideas/leak.md")).unwrap();
+            std::os::unix::fs::symlink(&root, source.join("changes/escape")).unwrap();
+            assert_eq!(kind(delete_idea(&source, "leak")), std::io::ErrorKind::InvalidInput);
+            assert!(root.join("victim.md").exists());
+            assert!(get_change_detail(&source, "test", "escape").is_none());
+            assert!(scan_ideas(&source, "test").is_empty());
+
+            std::fs::create_dir_all(source.join("changes/legit/specs/auth")).unwrap();
+            std::os::unix::fs::symlink(root.join("victim.md"), source.join("changes/legit/proposal.md")).unwrap();
+            std::os::unix::fs::symlink(root.join("tasks.md"), source.join("changes/legit/tasks.md")).unwrap();
+            std::os::unix::fs::symlink(root.join("victim.md"), source.join("changes/legit/specs/auth/spec.md")).unwrap();
+            let detail = get_change_detail(&source, "test", "legit").unwrap();
+            assert!(detail.proposal.is_none());
+            assert!(detail.tasks.is_none());
+            assert!(detail.specs.is_empty());
+            let legit = scan_changes(&source, "test").into_iter().find(|c| c.name == "legit").unwrap();
+            assert!(legit.task_stats.is_none());
+        }
+    }
 }
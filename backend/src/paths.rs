@@ -0,0 +1,126 @@
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum PathError {
+    #[error("Invalid path '{0}'")]
+    Invalid(String),
+    #[error("Path '{0}' is outside the source directory")]
+    OutsideSource(String),
+}
+
+impl From<PathError> for std::io::Error {
+    fn from(e: PathError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, e)
+    }
+}
+
+/// Resolve a source-relative path such as `specs/auth/spec.md` under `root`.
+/// Absolute paths, `..` components and symlinks leading outside the source are refused.
+/// The target itself does not need to exist, so this also guards files about to be written.
+pub fn resolve(root: &Path, relative: &str) -> Result<PathBuf, PathError> {
+    let invalid = || PathError::Invalid(relative.to_string());
+    if relative.is_empty() || relative.contains(['\0', '\\']) {
+        return Err(invalid());
+    }
+
+    let mut path = root.to_path_buf();
+    for component in Path::new(relative).components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return Err(invalid()),
+        }
+    }
+
+    confine(root, &path).map_err(|_| PathError::OutsideSource(relative.to_string()))?;
+    Ok(path)
+}
+
+/// Resolve `<dir>/<name><extension>`, where `name` is an id taken from a request
+/// (an idea or change name) and must be a single path component.
+pub fn resolve_entry(root: &Path, dir: &str, name: &str, extension: &str) -> Result<PathBuf, PathError> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\', '\0']) {
+        return Err(PathError::Invalid(name.to_string()));
+    }
+    resolve(root, &format!("{}/{}{}", dir, name, extension))
+}
+
+/// Check that `path`, once symlinks are followed, stays within `root`.
+/// For a path that does not exist yet its closest existing ancestor is checked;
+/// a dangling symlink is refused since its target cannot be verified.
+pub fn confine(root: &Path, path: &Path) -> Result<(), PathError> {
+    let outside = || PathError::OutsideSource(path.display().to_string());
+    // A missing source has nothing to escape through
+    let Ok(root) = root.canonicalize() else {
+        return Ok(());
+    };
+
+    for ancestor in path.ancestors() {
+        match ancestor.canonicalize() {
+            Ok(resolved) if resolved.starts_with(&root) => return Ok(()),
+            Ok(_) => return Err(outside()),
+            Err(_) if ancestor.symlink_metadata().is_ok() => return Err(outside()),
+            Err(_) => {}
+        }
+    }
+    Err(outside())
+}
+
+/// Read a file found under `root`, refusing one that symlinks outside of it.
+pub fn read_to_string(root: &Path, path: &Path) -> std::io::Result<String> {
+    confine(root, path)?;
+    std::fs::read_to_string(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_resolve_confines_paths_to_source() {
+        let root = TempDir::new("paths");
+        let source = root.join("openspec");
+        std::fs::create_dir_all(source.join("specs/auth")).unwrap();
+        std::fs::create_dir_all(source.join("ideas")).unwrap();
+        std::fs::write(root.join("secret.md"), "outside\n").unwrap();
+        std::fs::write(source.join("specs/auth/spec.md"), "# Auth\n").unwrap();
+
+        assert_eq!(resolve(&source, "specs/auth/spec.md"), Ok(source.join("specs/auth/spec.md")));
+        assert_eq!(resolve(&source, "./specs/new/spec.md"), Ok(source.join("specs/new/spec.md")));
+        assert_eq!(resolve_entry(&source, "ideas", "idea-1", ".md"), Ok(source.join("ideas/idea-1.md")));
+
+        for relative in ["../secret.md", "specs/../../secret.md", "/etc/passwd", "", "specs\\..\\x", "a\0b"] {
+            assert_eq!(resolve(&source, relative), Err(PathError::Invalid(relative.to_string())), "{:?}", relative);
+        }
+        for name in ["..", ".", "", "../secret", "nested/idea", "..\\secret"] {
+            assert_eq!(
+                resolve_entry(&source, "ideas", name, ".md"),
+                Err(PathError::Invalid(name.to_string())),
+                "{:?}",
+                name
+            );
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::symlink;
+            symlink(&root, source.join("specs/escape")).unwrap();
+            symlink(root.join("secret.md"), source.join("ideas/leak.md")).unwrap();
+            symlink(root.join("missing.md"), source.join("ideas/dangling.md")).unwrap();
+            symlink(source.join("specs/auth"), source.join("specs/alias")).unwrap();
+
+            let escapes = ["specs/escape/secret.md", "specs/escape/new.md", "ideas/leak.md", "ideas/dangling.md"];
+            for relative in escapes {
+                assert_eq!(
+                    resolve(&source, relative),
+                    Err(PathError::OutsideSource(relative.to_string())),
+                    "{:?}",
+                    relative
+                );
+            }
+            assert_eq!(resolve(&source, "specs/alias/spec.md"), Ok(source.join("specs/alias/spec.md")));
+        }
+    }
+}
@@ -0,0 +1,375 @@
+use crate::config::Source;
+use crate::parser::{archived_change_name, Change, ChangeStatus, Idea};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Md,
+    Json,
+    Csv,
+}
+
+impl ReportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ReportFormat::Md => "text/markdown; charset=utf-8",
+            ReportFormat::Json => "application/json",
+            ReportFormat::Csv => "text/csv; charset=utf-8",
+        }
+    }
+}
+
+/// Inclusive date range for archived changes and new ideas
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ReportRange {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl ReportRange {
+    /// Parse optional `YYYY-MM-DD` bounds; defaults to the seven days ending `today`
+    pub fn parse(from: Option<&str>, to: Option<&str>, today: NaiveDate) -> Result<Self, String> {
+        let parse = |value: &str| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| format!("invalid date '{}', expected YYYY-MM-DD", value))
+        };
+        let to = to.map(parse).transpose()?.unwrap_or(today);
+        let from = from.map(parse).transpose()?.unwrap_or(to - chrono::Days::new(6));
+        if from > to {
+            return Err(format!("from ({}) is after to ({})", from, to));
+        }
+        Ok(ReportRange { from, to })
+    }
+
+    fn contains(&self, date: NaiveDate) -> bool {
+        self.from <= date && date <= self.to
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusCounts {
+    pub draft: usize,
+    pub todo: usize,
+    pub in_progress: usize,
+    pub done: usize,
+    pub archived: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeProgress {
+    pub id: String,
+    pub name: String,
+    pub status: ChangeStatus,
+    pub tasks_done: usize,
+    pub tasks_total: usize,
+    /// None when the change has no tasks
+    pub percent: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedEntry {
+    pub id: String,
+    pub name: String,
+    pub archived_on: NaiveDate,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdeaEntry {
+    pub id: String,
+    pub title: String,
+    pub created_on: NaiveDate,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceReport {
+    pub source_id: String,
+    pub name: String,
+    pub changes: StatusCounts,
+    /// Task totals across active (non-archived) changes
+    pub tasks_done: usize,
+    pub tasks_total: usize,
+    pub percent: Option<u32>,
+    pub active: Vec<ChangeProgress>,
+    pub recently_archived: Vec<ArchivedEntry>,
+    pub new_ideas: Vec<IdeaEntry>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusReport {
+    pub range: ReportRange,
+    pub sources: Vec<SourceReport>,
+}
+
+fn percent(done: usize, total: usize) -> Option<u32> {
+    (total > 0).then(|| (done as f64 * 100.0 / total as f64).round() as u32)
+}
+
+fn status_label(status: ChangeStatus) -> &'static str {
+    match status {
+        ChangeStatus::Draft => "Draft",
+        ChangeStatus::Todo => "Todo",
+        ChangeStatus::InProgress => "In Progress",
+        ChangeStatus::Done => "Done",
+        ChangeStatus::Archived => "Archived",
+    }
+}
+
+/// Leading `YYYY-MM-DD` of a date or RFC 3339 timestamp
+fn date_prefix(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()
+}
+
+fn source_report(source: &Source, changes: &[Change], ideas: &[Idea], range: &ReportRange) -> SourceReport {
+    let mut counts = StatusCounts::default();
+    let mut active = Vec::new();
+    let mut recently_archived = Vec::new();
+
+    for change in changes.iter().filter(|c| c.source_id == source.id) {
+        counts.total += 1;
+        match change.status {
+            ChangeStatus::Draft => counts.draft += 1,
+            ChangeStatus::Todo => counts.todo += 1,
+            ChangeStatus::InProgress => counts.in_progress += 1,
+            ChangeStatus::Done => counts.done += 1,
+            ChangeStatus::Archived => counts.archived += 1,
+        }
+
+        if change.status == ChangeStatus::Archived {
+            if let Some(archived_on) = date_prefix(&change.name).filter(|d| range.contains(*d)) {
+                recently_archived.push(ArchivedEntry {
+                    id: change.id.clone(),
+                    name: archived_change_name(&change.name).to_string(),
+                    archived_on,
+                });
+            }
+        } else {
+            let (done, total) = change.task_stats.as_ref().map_or((0, 0), |t| (t.done, t.total));
+            active.push(ChangeProgress {
+                id: change.id.clone(),
+                name: change.name.clone(),
+                status: change.status,
+                tasks_done: done,
+                tasks_total: total,
+                percent: percent(done, total),
+            });
+        }
+    }
+    active.sort_by(|a, b| a.name.cmp(&b.name));
+    recently_archived.sort_by(|a, b| b.archived_on.cmp(&a.archived_on).then_with(|| a.name.cmp(&b.name)));
+
+    let mut new_ideas: Vec<IdeaEntry> = ideas
+        .iter()
+        .filter(|i| i.source_id == source.id)
+        .filter_map(|idea| {
+            let created_on = date_prefix(&idea.created_at).filter(|d| range.contains(*d))?;
+            Some(IdeaEntry { id: idea.id.clone(), title: idea.title.clone(), created_on })
+        })
+        .collect();
+    new_ideas.sort_by(|a, b| b.created_on.cmp(&a.created_on).then_with(|| a.title.cmp(&b.title)));
+
+    let tasks_done = active.iter().map(|c| c.tasks_done).sum();
+    let tasks_total = active.iter().map(|c| c.tasks_total).sum();
+    SourceReport {
+        source_id: source.id.clone(),
+        name: source.name.clone(),
+        changes: counts,
+        tasks_done,
+        tasks_total,
+        percent: percent(tasks_done, tasks_total),
+        active,
+        recently_archived,
+        new_ideas,
+    }
+}
+
+/// Summarize changes and ideas per valid source
+pub fn build(sources: &[Source], changes: &[Change], ideas: &[Idea], range: ReportRange) -> StatusReport {
+    StatusReport {
+        range,
+        sources: sources
+            .iter()
+            .filter(|s| s.valid)
+            .map(|source| source_report(source, changes, ideas, &range))
+            .collect(),
+    }
+}
+
+fn percent_text(percent: Option<u32>) -> String {
+    percent.map_or("-".to_string(), |p| format!("{}%", p))
+}
+
+pub fn to_markdown(report: &StatusReport) -> String {
+    let mut out = format!("# Status report {} to {}\n", report.range.from, report.range.to);
+    for source in &report.sources {
+        let c = &source.changes;
+        let _ = write!(
+            out,
+            "\n## {} (`{}`)\n\n\
+             | Draft | Todo | In Progress | Done | Archived | Total |\n\
+             |---:|---:|---:|---:|---:|---:|\n\
+             | {} | {} | {} | {} | {} | {} |\n\n",
+            source.name, source.source_id, c.draft, c.todo, c.in_progress, c.done, c.archived, c.total
+        );
+        let _ = writeln!(
+            out,
+            "Tasks in active changes: {}/{} done ({})",
+            source.tasks_done,
+            source.tasks_total,
+            percent_text(source.percent)
+        );
+
+        out.push_str("\n### Active changes\n\n");
+        if source.active.is_empty() {
+            out.push_str("None\n");
+        } else {
+            out.push_str("| Change | Status | Tasks | Complete |\n|---|---|---:|---:|\n");
+            for change in &source.active {
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {}/{} | {} |",
+                    change.name,
+                    status_label(change.status),
+                    change.tasks_done,
+                    change.tasks_total,
+                    percent_text(change.percent)
+                );
+            }
+        }
+
+        out.push_str("\n### Recently archived\n\n");
+        if source.recently_archived.is_empty() {
+            out.push_str("None\n");
+        }
+        for change in &source.recently_archived {
+            let _ = writeln!(out, "- {} {}", change.archived_on, change.name);
+        }
+
+        out.push_str("\n### New ideas\n\n");
+        if source.new_ideas.is_empty() {
+            out.push_str("None\n");
+        }
+        for idea in &source.new_ideas {
+            let _ = writeln!(out, "- {} {}", idea.created_on, idea.title);
+        }
+    }
+    out
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One row per source, for spreadsheets
+pub fn to_csv(report: &StatusReport) -> String {
+    let mut out = String::from(
+        "source,name,draft,todo,in_progress,done,archived,total,tasks_done,tasks_total,tasks_percent,archived_in_range,new_ideas\n",
+    );
+    for source in &report.sources {
+        let c = &source.changes;
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            csv_field(&source.source_id),
+            csv_field(&source.name),
+            c.draft,
+            c.todo,
+            c.in_progress,
+            c.done,
+            c.archived,
+            c.total,
+            source.tasks_done,
+            source.tasks_total,
+            source.percent.map_or(String::new(), |p| p.to_string()),
+            source.recently_archived.len(),
+            source.new_ideas.len()
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TaskStats;
+    use std::path::PathBuf;
+
+    fn change(name: &str, status: ChangeStatus, tasks: Option<(usize, usize)>) -> Change {
+        Change {
+            id: format!("demo/{}", name),
+            name: name.to_string(),
+            source_id: "demo".to_string(),
+            status,
+            has_proposal: true,
+            has_specs: false,
+            has_tasks: tasks.is_some(),
+            has_design: false,
+            task_stats: tasks.map(|(done, total)| TaskStats { done, total }),
+            git: None,
+        }
+    }
+
+    #[test]
+    fn test_status_report() {
+        let source = Source {
+            id: "demo".to_string(),
+            name: "Demo".to_string(),
+            path: PathBuf::from("/tmp/demo"),
+            valid: true,
+            writable: false,
+        };
+        let changes = vec![
+            change("add-login", ChangeStatus::InProgress, Some((1, 4))),
+            change("add-logout", ChangeStatus::Todo, Some((0, 0))),
+            change("2025-03-02-add-theme", ChangeStatus::Archived, Some((2, 2))),
+            change("2025-01-01-old", ChangeStatus::Archived, None),
+        ];
+        let ideas = vec![Idea {
+            id: "demo/idea-1".to_string(),
+            source_id: "demo".to_string(),
+            project_id: None,
+            title: "Dark, mode".to_string(),
+            description: String::new(),
+            created_at: "2025-03-04T10:00:00+00:00".to_string(),
+            updated_at: "2025-03-04T10:00:00+00:00".to_string(),
+            promoted_to: None,
+        }];
+        let today = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+        let range = ReportRange::parse(None, None, today).unwrap();
+        assert_eq!(range.from, NaiveDate::from_ymd_opt(2025, 2, 27).unwrap());
+        assert!(ReportRange::parse(Some("2025-03-06"), None, today).is_err());
+        assert!(ReportRange::parse(Some("yesterday"), None, today).is_err());
+
+        let report = build(&[source], &changes, &ideas, range);
+        let demo = &report.sources[0];
+        assert_eq!(
+            demo.changes,
+            StatusCounts { draft: 0, todo: 1, in_progress: 1, done: 0, archived: 2, total: 4 }
+        );
+        assert_eq!((demo.tasks_done, demo.tasks_total, demo.percent), (1, 4, Some(25)));
+        assert_eq!(demo.recently_archived.len(), 1);
+        assert_eq!(demo.recently_archived[0].name, "add-theme");
+        assert_eq!(demo.new_ideas.len(), 1);
+
+        let md = to_markdown(&report);
+        assert!(md.contains("| 0 | 1 | 1 | 0 | 2 | 4 |"));
+        assert!(md.contains("| add-login | In Progress | 1/4 | 25% |"));
+        assert!(md.contains("- 2025-03-02 add-theme"));
+
+        let csv = to_csv(&report);
+        assert_eq!(csv.lines().nth(1), Some("demo,Demo,0,1,1,0,2,4,1,4,25,1,1"));
+    }
+}
@@ -0,0 +1,345 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Weight of a match in the title relative to one in the body
+const TITLE_WEIGHT: f64 = 3.0;
+/// Bonus factor when the whole query appears verbatim in a field
+const PHRASE_BONUS: f64 = 1.5;
+/// Characters of context shown before the first match in a snippet
+const SNIPPET_LEAD: usize = 60;
+const SNIPPET_LENGTH: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntityKind {
+    Change,
+    Spec,
+    Idea,
+}
+
+/// Searchable text of one change, spec or idea
+#[derive(Debug, Clone)]
+pub struct Document {
+    pub kind: EntityKind,
+    pub id: String,
+    pub source_id: String,
+    pub title: String,
+    /// Named bodies, e.g. `("proposal", ...)` or `("specs/auth/spec.md", ...)`
+    pub fields: Vec<(String, String)>,
+}
+
+/// A token position: field 0 is the title, field `i + 1` is `fields[i]`
+#[derive(Debug, Clone, Copy)]
+struct Occurrence {
+    field: usize,
+    start: usize,
+    end: usize,
+}
+
+struct IndexedDocument {
+    doc: Document,
+    terms: HashMap<String, Vec<Occurrence>>,
+}
+
+impl IndexedDocument {
+    fn text(&self, field: usize) -> &str {
+        match field {
+            0 => &self.doc.title,
+            i => &self.doc.fields[i - 1].1,
+        }
+    }
+}
+
+/// Match position within a snippet, in characters
+#[derive(Debug, Clone, Serialize)]
+pub struct Highlight {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub kind: EntityKind,
+    pub id: String,
+    pub source_id: String,
+    pub title: String,
+    /// Field the snippet was taken from, `title` when only the title matched
+    pub field: String,
+    pub score: f64,
+    pub snippet: String,
+    pub highlights: Vec<Highlight>,
+}
+
+/// Split text into lowercase alphanumeric terms with their byte ranges
+fn tokenize(text: &str) -> Vec<(String, usize, usize)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                tokens.push((text[s..i].to_lowercase(), s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
+
+fn doc_key(kind: EntityKind, id: &str) -> String {
+    format!("{:?}:{}", kind, id)
+}
+
+/// Inverted index over change, spec and idea text.
+/// Terms map to the documents containing them; each document keeps its own
+/// term positions so it can be removed or replaced without a full rebuild.
+#[derive(Default)]
+pub struct SearchIndex {
+    docs: HashMap<String, IndexedDocument>,
+    postings: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl SearchIndex {
+    pub fn upsert(&mut self, doc: Document) {
+        let key = doc_key(doc.kind, &doc.id);
+        self.remove(doc.kind, &doc.id);
+
+        let mut terms: HashMap<String, Vec<Occurrence>> = HashMap::new();
+        let texts = std::iter::once(doc.title.as_str()).chain(doc.fields.iter().map(|(_, text)| text.as_str()));
+        for (field, text) in texts.enumerate() {
+            for (term, start, end) in tokenize(text) {
+                terms.entry(term).or_default().push(Occurrence { field, start, end });
+            }
+        }
+
+        for term in terms.keys() {
+            self.postings.entry(term.clone()).or_default().insert(key.clone());
+        }
+        self.docs.insert(key, IndexedDocument { doc, terms });
+    }
+
+    pub fn remove(&mut self, kind: EntityKind, id: &str) {
+        let key = doc_key(kind, id);
+        let Some(old) = self.docs.remove(&key) else {
+            return;
+        };
+        for term in old.terms.keys() {
+            if let Some(keys) = self.postings.get_mut(term) {
+                keys.remove(&key);
+                if keys.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+    }
+
+    /// Indexed terms matching a query term; the last query term also matches as a prefix
+    fn expand(&self, term: &str, prefix: bool) -> Vec<&String> {
+        if !prefix {
+            return self.postings.get_key_value(term).map(|(t, _)| t).into_iter().collect();
+        }
+        self.postings
+            .range(term.to_string()..)
+            .take_while(|(t, _)| t.starts_with(term))
+            .map(|(t, _)| t)
+            .collect()
+    }
+
+    /// Rank documents containing every query term, optionally limited to some sources
+    pub fn search(&self, query: &str, sources: Option<&[String]>, limit: usize) -> (usize, Vec<SearchHit>) {
+        let query_terms: Vec<String> = tokenize(query).into_iter().map(|(t, _, _)| t).collect();
+        if query_terms.is_empty() {
+            return (0, Vec::new());
+        }
+
+        let total_docs = self.docs.len() as f64;
+        let mut scores: Option<HashMap<&str, f64>> = None;
+        for (i, term) in query_terms.iter().enumerate() {
+            let mut term_scores: HashMap<&str, f64> = HashMap::new();
+            for expanded in self.expand(term, i == query_terms.len() - 1) {
+                let keys = &self.postings[expanded];
+                let idf = (1.0 + total_docs / keys.len() as f64).ln();
+                for key in keys {
+                    let occurrences = &self.docs[key].terms[expanded];
+                    let tf: f64 = occurrences
+                        .iter()
+                        .map(|o| if o.field == 0 { TITLE_WEIGHT } else { 1.0 })
+                        .sum();
+                    let best = term_scores.entry(key.as_str()).or_default();
+                    *best = best.max(idf * (1.0 + tf.ln()));
+                }
+            }
+            scores = Some(match scores {
+                None => term_scores,
+                Some(prev) => prev
+                    .into_iter()
+                    .filter_map(|(key, score)| term_scores.get(key).map(|s| (key, score + s)))
+                    .collect(),
+            });
+        }
+
+        let phrase = query.trim().to_lowercase();
+        let mut ranked: Vec<(&IndexedDocument, f64)> = scores
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(key, score)| (&self.docs[key], score))
+            .filter(|(d, _)| sources.is_none_or(|s| s.contains(&d.doc.source_id)))
+            .map(|(d, score)| {
+                let texts = std::iter::once(&d.doc.title).chain(d.doc.fields.iter().map(|(_, t)| t));
+                let has_phrase = query_terms.len() > 1 && texts.into_iter().any(|t| t.to_lowercase().contains(&phrase));
+                (d, if has_phrase { score * PHRASE_BONUS } else { score })
+            })
+            .collect();
+        ranked.sort_by(|(a, sa), (b, sb)| sb.total_cmp(sa).then_with(|| a.doc.id.cmp(&b.doc.id)));
+
+        let total = ranked.len();
+        let hits = ranked
+            .into_iter()
+            .take(limit)
+            .map(|(d, score)| self.hit(d, &query_terms, score))
+            .collect();
+        (total, hits)
+    }
+
+    fn hit(&self, d: &IndexedDocument, query_terms: &[String], score: f64) -> SearchHit {
+        let last = query_terms.len() - 1;
+        let mut occurrences: Vec<Occurrence> = d
+            .terms
+            .iter()
+            .filter(|(term, _)| {
+                query_terms
+                    .iter()
+                    .enumerate()
+                    .any(|(i, q)| *term == q || (i == last && term.starts_with(q.as_str())))
+            })
+            .flat_map(|(_, occ)| occ.iter().copied())
+            .collect();
+        occurrences.sort_by_key(|o| (o.field, o.start));
+
+        // Prefer the body field with the most matches; fall back to the title
+        let mut per_field: BTreeMap<usize, usize> = BTreeMap::new();
+        for o in occurrences.iter().filter(|o| o.field > 0) {
+            *per_field.entry(o.field).or_default() += 1;
+        }
+        let field = per_field
+            .iter()
+            .max_by(|(fa, ca), (fb, cb)| ca.cmp(cb).then(fb.cmp(fa)))
+            .map_or(0, |(f, _)| *f);
+        let in_field: Vec<Occurrence> = occurrences.into_iter().filter(|o| o.field == field).collect();
+
+        let (snippet, highlights) = snippet(d.text(field), &in_field);
+        SearchHit {
+            kind: d.doc.kind,
+            id: d.doc.id.clone(),
+            source_id: d.doc.source_id.clone(),
+            title: d.doc.title.clone(),
+            field: match field {
+                0 => "title".to_string(),
+                i => d.doc.fields[i - 1].0.clone(),
+            },
+            score,
+            snippet,
+            highlights,
+        }
+    }
+}
+
+/// Cut a window of `text` around the first occurrence, with whitespace flattened
+/// to single-line form and highlights as character offsets into the snippet
+fn snippet(text: &str, occurrences: &[Occurrence]) -> (String, Vec<Highlight>) {
+    let first = occurrences.first().map_or(0, |o| o.start);
+    let lead_start = text[..first]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_LEAD - 1)
+        .map_or(0, |(i, _)| i);
+    // Start at a word boundary inside the lead when possible
+    let boundary = text[lead_start..first]
+        .char_indices()
+        .find(|(_, c)| c.is_whitespace())
+        .map(|(i, c)| lead_start + i + c.len_utf8());
+    let start = match boundary {
+        Some(b) if lead_start > 0 => b,
+        _ => lead_start,
+    };
+    let end = text[start..]
+        .char_indices()
+        .nth(SNIPPET_LENGTH)
+        .map_or(text.len(), |(i, _)| start + i);
+
+    let window = &text[start..end];
+    let snippet: String = window.chars().map(|c| if c.is_whitespace() { ' ' } else { c }).collect();
+    let char_offset = |byte: usize| window[..byte - start].chars().count();
+    let highlights = occurrences
+        .iter()
+        .filter(|o| o.start >= start && o.end <= end)
+        .map(|o| Highlight {
+            start: char_offset(o.start),
+            end: char_offset(o.end),
+        })
+        .collect();
+    (snippet, highlights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(kind: EntityKind, id: &str, title: &str, body: &str) -> Document {
+        Document {
+            kind,
+            id: id.to_string(),
+            source_id: id.split('/').next().unwrap().to_string(),
+            title: title.to_string(),
+            fields: vec![("proposal".to_string(), body.to_string())],
+        }
+    }
+
+    #[test]
+    fn test_search_ranks_and_highlights() {
+        let mut index = SearchIndex::default();
+        index.upsert(doc(
+            EntityKind::Change,
+            "a/add-sse-resume",
+            "add-sse-resume",
+            "## Why\nClients lose events when the SSE reconnect happens after a restart.",
+        ));
+        index.upsert(doc(EntityKind::Idea, "b/idea-1", "Dashboard", "Show the SSE status on the wall."));
+        index.upsert(doc(EntityKind::Spec, "a/auth", "auth", "Tokens expire after an hour."));
+
+        let (total, hits) = index.search("SSE reconn", None, 10);
+        assert_eq!(total, 1);
+        assert_eq!(hits[0].id, "a/add-sse-resume");
+        assert_eq!(hits[0].field, "proposal");
+        let h = &hits[0].highlights;
+        assert_eq!(h.len(), 2);
+        let chars: Vec<char> = hits[0].snippet.chars().collect();
+        assert_eq!(chars[h[0].start..h[0].end].iter().collect::<String>(), "SSE");
+        assert_eq!(chars[h[1].start..h[1].end].iter().collect::<String>(), "reconnect");
+        assert!(!hits[0].snippet.contains('\n'));
+
+        let (total, hits) = index.search("sse", None, 10);
+        assert_eq!(total, 2);
+        // The title match outranks a single body match
+        assert_eq!(hits[0].id, "a/add-sse-resume");
+
+        let (total, _) = index.search("sse", Some(&["b".to_string()]), 10);
+        assert_eq!(total, 1);
+    }
+
+    #[test]
+    fn test_search_index_replaces_and_removes_documents() {
+        let mut index = SearchIndex::default();
+        index.upsert(doc(EntityKind::Idea, "a/idea-1", "Idea", "old wording"));
+        index.upsert(doc(EntityKind::Idea, "a/idea-1", "Idea", "new wording"));
+
+        assert_eq!(index.search("old", None, 10).0, 0);
+        assert_eq!(index.search("new", None, 10).0, 1);
+
+        index.remove(EntityKind::Idea, "a/idea-1");
+        assert_eq!(index.search("wording", None, 10).0, 0);
+        assert!(index.postings.is_empty());
+    }
+}
@@ -0,0 +1,1113 @@
+use serde::Serialize;
+
+/// Structured view of a source-of-truth spec file.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedSpec {
+    pub title: Option<String>,
+    pub purpose: Option<String>,
+    pub requirements: Vec<Requirement>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Requirement {
+    pub name: String,
+    /// Normative text between the requirement header and its first scenario
+    pub description: String,
+    pub scenarios: Vec<Scenario>,
+    /// Full markdown block, header included, without trailing blank lines
+    pub raw: String,
+    /// 1-based line number of the `### Requirement:` header
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Scenario {
+    pub name: String,
+    pub steps: Vec<ScenarioStep>,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepKeyword {
+    Given,
+    When,
+    Then,
+    And,
+    But,
+}
+
+impl StepKeyword {
+    pub fn label(self) -> &'static str {
+        match self {
+            StepKeyword::Given => "GIVEN",
+            StepKeyword::When => "WHEN",
+            StepKeyword::Then => "THEN",
+            StepKeyword::And => "AND",
+            StepKeyword::But => "BUT",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioStep {
+    /// None for bullets that do not start with GIVEN/WHEN/THEN/AND/BUT
+    pub keyword: Option<StepKeyword>,
+    pub text: String,
+    pub line: usize,
+}
+
+/// Requirement operations declared by a change's delta spec for one capability.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeltaSpec {
+    pub capability: String,
+    pub path: String,
+    pub added: Vec<Requirement>,
+    pub modified: Vec<Requirement>,
+    pub removed: Vec<RemovedRequirement>,
+    pub renamed: Vec<RenamedRequirement>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovedRequirement {
+    pub name: String,
+    pub reason: Option<String>,
+    pub migration: Option<String>,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamedRequirement {
+    pub from: String,
+    pub to: String,
+    pub line: usize,
+}
+
+/// The kind of a `## <OP> Requirements` section in a delta spec
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DeltaOp {
+    Added,
+    Modified,
+    Removed,
+    Renamed,
+}
+
+impl DeltaOp {
+    pub(crate) fn from_section(title: &str) -> Option<Self> {
+        match title.split_whitespace().next()?.to_ascii_uppercase().as_str() {
+            "ADDED" => Some(Self::Added),
+            "MODIFIED" => Some(Self::Modified),
+            "REMOVED" => Some(Self::Removed),
+            "RENAMED" => Some(Self::Renamed),
+            _ => None,
+        }
+    }
+}
+
+const REQUIREMENT_PREFIX: &str = "### Requirement:";
+const SCENARIO_PREFIX: &str = "#### Scenario:";
+
+/// A markdown line classified by the heading levels OpenSpec cares about.
+pub(crate) enum Line<'a> {
+    Title(&'a str),
+    Section(&'a str),
+    Requirement(&'a str),
+    /// Any other `###` heading, which closes the current requirement
+    Heading,
+    Scenario(&'a str),
+    Text(&'a str),
+}
+
+/// Classify every line of `content`, treating fenced code blocks as plain text.
+pub(crate) fn classify_lines(content: &str) -> Vec<Line<'_>> {
+    let mut in_fence = false;
+
+    content
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_end();
+            if trimmed.trim_start().starts_with("```") {
+                in_fence = !in_fence;
+                return Line::Text(line);
+            }
+            if in_fence {
+                return Line::Text(line);
+            }
+
+            if let Some(name) = trimmed.strip_prefix(REQUIREMENT_PREFIX) {
+                Line::Requirement(name.trim())
+            } else if let Some(name) = trimmed.strip_prefix(SCENARIO_PREFIX) {
+                Line::Scenario(name.trim())
+            } else if trimmed.starts_with("#### ") {
+                Line::Text(line)
+            } else if trimmed.starts_with("### ") {
+                Line::Heading
+            } else if let Some(name) = trimmed.strip_prefix("## ") {
+                Line::Section(name.trim())
+            } else if let Some(name) = trimmed.strip_prefix("# ") {
+                Line::Title(name.trim())
+            } else {
+                Line::Text(line)
+            }
+        })
+        .collect()
+}
+
+/// Parse a `- **WHEN** ...` style bullet, or an unbulleted `**When** ...` line, into a scenario step
+fn parse_step(line: &str, line_no: usize) -> Option<ScenarioStep> {
+    let trimmed = line.trim_start();
+    let bullet = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* "));
+    let rest = match bullet {
+        Some(rest) => rest.trim_start(),
+        None if trimmed.starts_with("**") => trimmed,
+        None => return None,
+    };
+
+    let (word, text) = match rest.strip_prefix("**") {
+        Some(bold) => {
+            let end = bold.find("**")?;
+            (&bold[..end], &bold[end + 2..])
+        }
+        None => rest.split_at(rest.find(char::is_whitespace).unwrap_or(rest.len())),
+    };
+
+    let keyword = match word.trim_end_matches(':').to_ascii_uppercase().as_str() {
+        "GIVEN" => Some(StepKeyword::Given),
+        "WHEN" => Some(StepKeyword::When),
+        "THEN" => Some(StepKeyword::Then),
+        "AND" => Some(StepKeyword::And),
+        "BUT" => Some(StepKeyword::But),
+        _ => None,
+    };
+    // Without a bullet only a bold keyword marks a step; other bold text is prose
+    if bullet.is_none() && keyword.is_none() {
+        return None;
+    }
+
+    let text = match keyword {
+        Some(_) => text.trim_start_matches(':').trim(),
+        None => rest.trim(),
+    };
+
+    Some(ScenarioStep {
+        keyword,
+        text: text.to_string(),
+        line: line_no,
+    })
+}
+
+fn join_trimmed(lines: &[&str]) -> String {
+    lines.join("\n").trim().to_string()
+}
+
+fn section_text(section: &Section) -> String {
+    let lines: Vec<&str> = section.text.iter().map(|(_, l)| *l).collect();
+    join_trimmed(&lines)
+}
+
+/// Builds one requirement block as lines are fed in.
+struct RequirementBuilder<'a> {
+    name: String,
+    line: usize,
+    description: Vec<&'a str>,
+    scenarios: Vec<Scenario>,
+    raw: Vec<&'a str>,
+}
+
+impl<'a> RequirementBuilder<'a> {
+    fn new(name: &str, header: &'a str, line: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            line,
+            description: Vec::new(),
+            scenarios: Vec::new(),
+            raw: vec![header],
+        }
+    }
+
+    fn push_scenario(&mut self, name: &str, header: &'a str, line: usize) {
+        self.scenarios.push(Scenario {
+            name: name.to_string(),
+            steps: Vec::new(),
+            line,
+        });
+        self.raw.push(header);
+    }
+
+    fn push_text(&mut self, text: &'a str, line: usize) {
+        self.raw.push(text);
+        match self.scenarios.last_mut() {
+            Some(scenario) => {
+                if let Some(step) = parse_step(text, line) {
+                    scenario.steps.push(step);
+                }
+            }
+            None => self.description.push(text),
+        }
+    }
+
+    fn finish(mut self) -> Requirement {
+        while self.raw.last().is_some_and(|l| l.trim().is_empty()) {
+            self.raw.pop();
+        }
+        Requirement {
+            name: self.name,
+            description: join_trimmed(&self.description),
+            scenarios: self.scenarios,
+            raw: self.raw.join("\n"),
+            line: self.line,
+        }
+    }
+}
+
+/// A `##` section of a spec together with the requirements declared in it.
+pub(crate) struct Section<'a> {
+    pub title: &'a str,
+    /// Numbered lines of the section that are not part of a requirement block
+    pub text: Vec<(usize, &'a str)>,
+    pub requirements: Vec<Requirement>,
+}
+
+/// Split a spec into its `##` sections, parsing requirement blocks along the way.
+/// Content before the first section (title, preamble) is returned as a section
+/// with an empty title.
+pub(crate) fn parse_sections(content: &str) -> (Option<String>, Vec<Section<'_>>) {
+    let raw_lines: Vec<&str> = content.lines().collect();
+    let mut title = None;
+    let mut sections = vec![Section {
+        title: "",
+        text: Vec::new(),
+        requirements: Vec::new(),
+    }];
+    let mut current: Option<RequirementBuilder> = None;
+
+    for (i, line) in classify_lines(content).into_iter().enumerate() {
+        let line_no = i + 1;
+        let raw = raw_lines[i];
+
+        if !matches!(line, Line::Scenario(_) | Line::Text(_)) {
+            if let Some(builder) = current.take() {
+                sections.last_mut().unwrap().requirements.push(builder.finish());
+            }
+        }
+
+        match line {
+            Line::Title(name) => {
+                if title.is_none() {
+                    title = Some(name.to_string());
+                }
+                sections.last_mut().unwrap().text.push((line_no, raw));
+            }
+            Line::Section(name) => sections.push(Section {
+                title: name,
+                text: Vec::new(),
+                requirements: Vec::new(),
+            }),
+            Line::Requirement(name) => current = Some(RequirementBuilder::new(name, raw, line_no)),
+            Line::Heading => sections.last_mut().unwrap().text.push((line_no, raw)),
+            Line::Scenario(name) => match current.as_mut() {
+                Some(builder) => builder.push_scenario(name, raw, line_no),
+                None => sections.last_mut().unwrap().text.push((line_no, raw)),
+            },
+            Line::Text(text) => match current.as_mut() {
+                Some(builder) => builder.push_text(text, line_no),
+                None => sections.last_mut().unwrap().text.push((line_no, raw)),
+            },
+        }
+    }
+
+    if let Some(builder) = current.take() {
+        sections.last_mut().unwrap().requirements.push(builder.finish());
+    }
+
+    (title, sections)
+}
+
+/// Parse a source-of-truth spec into purpose and requirements
+pub fn parse_spec(content: &str) -> ParsedSpec {
+    let (title, sections) = parse_sections(content);
+
+    let purpose = sections
+        .iter()
+        .find(|s| s.title.eq_ignore_ascii_case("purpose"))
+        .map(section_text)
+        .filter(|p| !p.is_empty());
+
+    let requirements = sections.into_iter().flat_map(|s| s.requirements).collect();
+
+    ParsedSpec {
+        title,
+        purpose,
+        requirements,
+    }
+}
+
+/// Strip an optional `- ` bullet and a case-insensitive, optionally bold `label:` prefix
+fn strip_label<'a>(line: &'a str, label: &str) -> Option<&'a str> {
+    let line = line.trim();
+    let line = line.strip_prefix("- ").unwrap_or(line).trim_start();
+    let line = line.strip_prefix("**").unwrap_or(line);
+    // `get` treats a multi-byte character across the label boundary as a mismatch instead of panicking
+    if !line.get(..label.len()).is_some_and(|prefix| prefix.eq_ignore_ascii_case(label)) {
+        return None;
+    }
+    let rest = line.get(label.len()..)?.trim_start_matches("**");
+    let rest = rest.strip_prefix(':')?.trim_start_matches("**");
+    Some(rest.trim())
+}
+
+/// Extract the requirement name from a RENAMED entry such as `` `### Requirement: Login` ``
+fn renamed_target(value: &str) -> String {
+    let value = value.trim().trim_matches('`').trim();
+    value
+        .strip_prefix(REQUIREMENT_PREFIX)
+        .unwrap_or(value)
+        .trim()
+        .to_string()
+}
+
+fn removed_requirement(requirement: Requirement) -> RemovedRequirement {
+    let mut reason = None;
+    let mut migration = None;
+    for line in requirement.description.lines() {
+        if let Some(value) = strip_label(line, "Reason") {
+            reason = Some(value.to_string());
+        } else if let Some(value) = strip_label(line, "Migration") {
+            migration = Some(value.to_string());
+        }
+    }
+
+    RemovedRequirement {
+        name: requirement.name,
+        reason,
+        migration,
+        line: requirement.line,
+    }
+}
+
+/// Parse a change's delta spec into ADDED / MODIFIED / REMOVED / RENAMED operations.
+/// `path` is relative to the change's `specs/` directory, e.g. `chat/spec.md`.
+pub fn parse_delta_spec(path: &str, content: &str) -> DeltaSpec {
+    let (_, sections) = parse_sections(content);
+    let mut delta = DeltaSpec {
+        capability: path.replace("/spec.md", "").replace(".md", ""),
+        path: path.to_string(),
+        added: Vec::new(),
+        modified: Vec::new(),
+        removed: Vec::new(),
+        renamed: Vec::new(),
+    };
+
+    for section in sections {
+        match DeltaOp::from_section(section.title) {
+            Some(DeltaOp::Added) => delta.added.extend(section.requirements),
+            Some(DeltaOp::Modified) => delta.modified.extend(section.requirements),
+            Some(DeltaOp::Removed) => delta
+                .removed
+                .extend(section.requirements.into_iter().map(removed_requirement)),
+            Some(DeltaOp::Renamed) => {
+                let mut from: Option<(usize, String)> = None;
+                for (line_no, line) in section.text {
+                    if let Some(value) = strip_label(line, "FROM") {
+                        from = Some((line_no, renamed_target(value)));
+                    } else if let Some(value) = strip_label(line, "TO") {
+                        if let Some((line, from)) = from.take() {
+                            delta.renamed.push(RenamedRequirement {
+                                from,
+                                to: renamed_target(value),
+                                line,
+                            });
+                        }
+                    }
+                }
+            }
+            None => {}
+        }
+    }
+
+    delta
+}
+
+/// Result of applying a delta spec to the matching source-of-truth spec.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergedSpec {
+    pub capability: String,
+    /// Path relative to the source's `specs/` directory
+    pub path: String,
+    /// True when the capability has no source-of-truth spec yet
+    pub is_new: bool,
+    pub content: String,
+    pub requirements: Vec<RequirementDiff>,
+    /// Operations that could not be applied, e.g. modifying a missing requirement
+    pub conflicts: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequirementChange {
+    Added,
+    Modified,
+    Removed,
+    Renamed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequirementDiff {
+    pub name: String,
+    pub change: RequirementChange,
+    /// Name before a RENAMED operation
+    pub previous_name: Option<String>,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    /// Scenario-level changes of a modified requirement, when both versions are known
+    pub scenarios: Vec<ScenarioDiff>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioDiff {
+    pub name: String,
+    /// Added, Modified or Removed
+    pub change: RequirementChange,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+/// A piece of a spec being rebuilt during a merge: an untouched line of the
+/// original, or a requirement block that operations may rewrite
+enum MergeBlock {
+    Line(String),
+    Requirement { name: String, raw: String },
+}
+
+impl MergeBlock {
+    fn text(&self) -> &str {
+        match self {
+            MergeBlock::Line(line) => line,
+            MergeBlock::Requirement { raw, .. } => raw,
+        }
+    }
+
+    fn is_blank(&self) -> bool {
+        matches!(self, MergeBlock::Line(line) if line.trim().is_empty())
+    }
+}
+
+/// A spec split into blocks, each tagged with the title of its `##` section.
+/// Rendering an unmodified document gives back the original bytes.
+struct MergeDocument {
+    blocks: Vec<(String, MergeBlock)>,
+}
+
+impl MergeDocument {
+    fn parse(content: &str) -> Self {
+        let (_, sections) = parse_sections(content);
+        let mut requirements = sections.into_iter().flat_map(|s| s.requirements).peekable();
+        let classified = classify_lines(content);
+        // Unlike `lines()`, this keeps carriage returns and the final newline
+        let lines: Vec<&str> = content.split('\n').collect();
+
+        let mut blocks = Vec::new();
+        let mut title = String::new();
+        let mut i = 0;
+        while i < lines.len() {
+            if let Some(Line::Section(name)) = classified.get(i) {
+                title = name.to_string();
+            }
+            let block = match requirements.next_if(|r| r.line == i + 1) {
+                Some(requirement) => {
+                    let end = i + requirement.raw.lines().count().max(1);
+                    let raw = lines[i..end].join("\n");
+                    i = end;
+                    MergeBlock::Requirement {
+                        name: requirement.name,
+                        raw,
+                    }
+                }
+                None => {
+                    i += 1;
+                    MergeBlock::Line(lines[i - 1].to_string())
+                }
+            };
+            blocks.push((title.clone(), block));
+        }
+
+        Self { blocks }
+    }
+
+    fn find(&self, name: &str) -> Option<usize> {
+        self.blocks
+            .iter()
+            .position(|(_, block)| matches!(block, MergeBlock::Requirement { name: n, .. } if same_name(n, name)))
+    }
+
+    fn requirement_mut(&mut self, index: usize) -> (&mut String, &mut String) {
+        match &mut self.blocks[index].1 {
+            MergeBlock::Requirement { name, raw } => (name, raw),
+            MergeBlock::Line(_) => unreachable!("find only returns requirement blocks"),
+        }
+    }
+
+    /// Remove a requirement block along with one of the blank lines around it
+    fn remove(&mut self, index: usize) -> (String, String) {
+        let (_, block) = self.blocks.remove(index);
+        let blank_before = index == 0 || self.blocks[index - 1].1.is_blank();
+        if blank_before && self.blocks.get(index).is_some_and(|(_, b)| b.is_blank()) {
+            self.blocks.remove(index);
+        }
+        match block {
+            MergeBlock::Requirement { name, raw } => (name, raw),
+            MergeBlock::Line(_) => unreachable!("find only returns requirement blocks"),
+        }
+    }
+
+    /// Append a requirement to the `## Requirements` section, creating the section if needed
+    fn add(&mut self, name: &str, raw: &str) {
+        let in_section = |(title, _): &(String, MergeBlock)| title.eq_ignore_ascii_case("requirements");
+        let last_requirement = self
+            .blocks
+            .iter()
+            .rposition(|entry| in_section(entry) && matches!(entry.1, MergeBlock::Requirement { .. }));
+        let last_in_section = self.blocks.iter().rposition(|entry| in_section(entry) && !entry.1.is_blank());
+
+        let mut insert = Vec::new();
+        let at = match last_requirement.or(last_in_section) {
+            Some(anchor) => anchor + 1,
+            None => {
+                let end = self.blocks.iter().rposition(|(_, b)| !b.is_blank()).map_or(0, |i| i + 1);
+                if end > 0 {
+                    insert.push(MergeBlock::Line(String::new()));
+                }
+                insert.push(MergeBlock::Line("## Requirements".to_string()));
+                end
+            }
+        };
+        if at > 0 {
+            insert.push(MergeBlock::Line(String::new()));
+        }
+        insert.push(MergeBlock::Requirement {
+            name: name.to_string(),
+            raw: raw.to_string(),
+        });
+        let title = "Requirements".to_string();
+        self.blocks
+            .splice(at..at, insert.into_iter().map(|block| (title.clone(), block)));
+    }
+
+    fn render(&self) -> String {
+        self.blocks
+            .iter()
+            .map(|(_, block)| block.text())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Apply a delta spec to the current spec content, in OpenSpec order:
+/// RENAMED, REMOVED, MODIFIED, then ADDED.
+/// Only the requirement blocks an operation touches are rewritten; every other
+/// byte of `base` is kept as is.
+/// `base` is None when the capability does not exist yet, in which case a new
+/// spec is scaffolded for `change_name`.
+pub fn merge_spec(base: Option<&str>, delta: &DeltaSpec, change_name: &str) -> MergedSpec {
+    let is_new = base.is_none();
+    let scaffold = format!(
+        "# {} Specification\n\n## Purpose\nTBD - created by archiving change {}. Update Purpose after archive.\n\n## Requirements\n",
+        delta.capability, change_name
+    );
+    let mut document = MergeDocument::parse(base.unwrap_or(&scaffold));
+
+    let mut diffs = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for rename in &delta.renamed {
+        if document.find(&rename.to).is_some() {
+            conflicts.push(format!("RENAMED target '{}' already exists", rename.to));
+            continue;
+        }
+        let Some(i) = document.find(&rename.from) else {
+            conflicts.push(format!("RENAMED requirement '{}' not found", rename.from));
+            continue;
+        };
+        let (name, raw) = document.requirement_mut(i);
+        let before = raw.clone();
+        let header = format!("{} {}", REQUIREMENT_PREFIX, rename.to);
+        *raw = match before.split_once('\n') {
+            Some((_, body)) => format!("{}\n{}", header, body),
+            None => header,
+        };
+        *name = rename.to.clone();
+        diffs.push(RequirementDiff {
+            name: rename.to.clone(),
+            change: RequirementChange::Renamed,
+            previous_name: Some(rename.from.clone()),
+            before: Some(before),
+            after: Some(raw.clone()),
+            scenarios: Vec::new(),
+        });
+    }
+
+    for removed in &delta.removed {
+        let Some(i) = document.find(&removed.name) else {
+            conflicts.push(format!("REMOVED requirement '{}' not found", removed.name));
+            continue;
+        };
+        let (name, raw) = document.remove(i);
+        diffs.push(RequirementDiff {
+            name,
+            change: RequirementChange::Removed,
+            previous_name: None,
+            before: Some(raw),
+            after: None,
+            scenarios: Vec::new(),
+        });
+    }
+
+    for modified in &delta.modified {
+        let Some(i) = document.find(&modified.name) else {
+            conflicts.push(format!("MODIFIED requirement '{}' not found", modified.name));
+            continue;
+        };
+        let (_, raw) = document.requirement_mut(i);
+        let before = std::mem::replace(raw, modified.raw.clone());
+        // A rename followed by a modification is reported once, as a modification
+        diffs.retain(|d| !(d.change == RequirementChange::Renamed && same_name(&d.name, &modified.name)));
+        diffs.push(RequirementDiff {
+            name: modified.name.clone(),
+            change: RequirementChange::Modified,
+            previous_name: None,
+            before: Some(before),
+            after: Some(modified.raw.clone()),
+            scenarios: Vec::new(),
+        });
+    }
+
+    for added in &delta.added {
+        if document.find(&added.name).is_some() {
+            conflicts.push(format!("ADDED requirement '{}' already exists", added.name));
+            continue;
+        }
+        document.add(&added.name, &added.raw);
+        diffs.push(RequirementDiff {
+            name: added.name.clone(),
+            change: RequirementChange::Added,
+            previous_name: None,
+            before: None,
+            after: Some(added.raw.clone()),
+            scenarios: Vec::new(),
+        });
+    }
+
+    MergedSpec {
+        capability: delta.capability.clone(),
+        path: delta.path.clone(),
+        is_new,
+        content: document.render(),
+        requirements: diffs,
+        conflicts,
+    }
+}
+
+/// Requirement body without its header line, for comparing across renames
+fn requirement_body(requirement: &Requirement) -> &str {
+    requirement.raw.split_once('\n').map_or("", |(_, body)| body).trim()
+}
+
+fn scenario_text(scenario: &Scenario) -> String {
+    scenario
+        .steps
+        .iter()
+        .map(|step| match step.keyword {
+            Some(keyword) => format!("- **{}** {}", keyword.label(), step.text),
+            None => format!("- {}", step.text),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn scenario_diffs(before: &Requirement, after: &Requirement) -> Vec<ScenarioDiff> {
+    let mut diffs = Vec::new();
+    for new in &after.scenarios {
+        let text = scenario_text(new);
+        match before.scenarios.iter().find(|old| same_name(&old.name, &new.name)) {
+            Some(old) => {
+                let old_text = scenario_text(old);
+                if old_text != text {
+                    diffs.push(ScenarioDiff {
+                        name: new.name.clone(),
+                        change: RequirementChange::Modified,
+                        before: Some(old_text),
+                        after: Some(text),
+                    });
+                }
+            }
+            None => diffs.push(ScenarioDiff {
+                name: new.name.clone(),
+                change: RequirementChange::Added,
+                before: None,
+                after: Some(text),
+            }),
+        }
+    }
+    for old in &before.scenarios {
+        if !after.scenarios.iter().any(|new| same_name(&new.name, &old.name)) {
+            diffs.push(ScenarioDiff {
+                name: old.name.clone(),
+                change: RequirementChange::Removed,
+                before: Some(scenario_text(old)),
+                after: None,
+            });
+        }
+    }
+    diffs
+}
+
+/// Compare two versions of a spec requirement by requirement. A requirement
+/// whose name changed but whose body did not is reported as renamed.
+pub fn diff_spec(before: &str, after: &str) -> Vec<RequirementDiff> {
+    let old = parse_spec(before).requirements;
+    let new = parse_spec(after).requirements;
+    let mut unmatched: Vec<&Requirement> = old
+        .iter()
+        .filter(|o| !new.iter().any(|n| same_name(&n.name, &o.name)))
+        .collect();
+
+    let mut diffs = Vec::new();
+    for requirement in &new {
+        match old.iter().find(|o| same_name(&o.name, &requirement.name)) {
+            Some(previous) => {
+                if requirement_body(previous) != requirement_body(requirement) {
+                    diffs.push(RequirementDiff {
+                        name: requirement.name.clone(),
+                        change: RequirementChange::Modified,
+                        previous_name: None,
+                        before: Some(previous.raw.clone()),
+                        after: Some(requirement.raw.clone()),
+                        scenarios: scenario_diffs(previous, requirement),
+                    });
+                }
+            }
+            None => match unmatched.iter().position(|o| requirement_body(o) == requirement_body(requirement)) {
+                Some(i) => {
+                    let previous = unmatched.remove(i);
+                    diffs.push(RequirementDiff {
+                        name: requirement.name.clone(),
+                        change: RequirementChange::Renamed,
+                        previous_name: Some(previous.name.clone()),
+                        before: Some(previous.raw.clone()),
+                        after: Some(requirement.raw.clone()),
+                        scenarios: Vec::new(),
+                    });
+                }
+                None => diffs.push(RequirementDiff {
+                    name: requirement.name.clone(),
+                    change: RequirementChange::Added,
+                    previous_name: None,
+                    before: None,
+                    after: Some(requirement.raw.clone()),
+                    scenarios: Vec::new(),
+                }),
+            },
+        }
+    }
+    for previous in unmatched {
+        diffs.push(RequirementDiff {
+            name: previous.name.clone(),
+            change: RequirementChange::Removed,
+            previous_name: None,
+            before: Some(previous.raw.clone()),
+            after: None,
+            scenarios: Vec::new(),
+        });
+    }
+    diffs
+}
+
+/// Unified line diff between two texts, with the number of added and removed lines
+pub fn line_diff(before: &str, after: &str, from_label: &str, to_label: &str) -> (String, usize, usize) {
+    let diff = similar::TextDiff::from_lines(before, after);
+    let (mut additions, mut deletions) = (0, 0);
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            similar::ChangeTag::Insert => additions += 1,
+            similar::ChangeTag::Delete => deletions += 1,
+            similar::ChangeTag::Equal => {}
+        }
+    }
+    let unified = diff.unified_diff().context_radius(3).header(from_label, to_label).to_string();
+    (unified, additions, deletions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"# chat Specification
+
+## Purpose
+Provides a chat interface.
+## Requirements
+### Requirement: Send Message
+The system SHALL allow users to send messages.
+
+#### Scenario: User sends a message
+- **GIVEN** the chat is open
+- **WHEN** the user submits a message
+- **THEN** the message is sent
+- **AND** it appears in the thread
+
+### Requirement: Chat History
+The system SHALL persist chat history.
+
+```markdown
+### Requirement: Not a real requirement
+```
+
+#### Scenario: History loads
+- GIVEN previous messages
+- WHEN the app opens
+- THEN they are displayed
+- a note without keyword
+"#;
+
+    #[test]
+    fn test_parse_spec_structure() {
+        let spec = parse_spec(SPEC);
+        assert_eq!(spec.title.as_deref(), Some("chat Specification"));
+        assert_eq!(spec.purpose.as_deref(), Some("Provides a chat interface."));
+        assert_eq!(spec.requirements.len(), 2);
+
+        let send = &spec.requirements[0];
+        assert_eq!(send.name, "Send Message");
+        assert_eq!(send.line, 6);
+        assert_eq!(send.description, "The system SHALL allow users to send messages.");
+        assert_eq!(send.scenarios.len(), 1);
+        assert!(send.raw.starts_with("### Requirement: Send Message"));
+        assert!(send.raw.ends_with("- **AND** it appears in the thread"));
+
+        let steps = &send.scenarios[0].steps;
+        assert_eq!(steps.len(), 4);
+        assert_eq!(steps[0].keyword, Some(StepKeyword::Given));
+        assert_eq!(steps[0].text, "the chat is open");
+        assert_eq!(steps[3].keyword, Some(StepKeyword::And));
+    }
+
+    #[test]
+    fn test_parse_spec_ignores_fenced_headers() {
+        let spec = parse_spec(SPEC);
+        let history = &spec.requirements[1];
+        assert_eq!(history.name, "Chat History");
+        assert!(history.description.contains("Not a real requirement"));
+
+        let steps = &history.scenarios[0].steps;
+        assert_eq!(steps.len(), 4);
+        assert_eq!(steps[1].keyword, Some(StepKeyword::When));
+        assert_eq!(steps[1].text, "the app opens");
+        assert_eq!(steps[3].keyword, None);
+        assert_eq!(steps[3].text, "a note without keyword");
+    }
+
+    #[test]
+    fn test_parse_unbulleted_steps() {
+        let spec = parse_spec(include_str!("../../example-openspec/specs/test-environment/spec.md"));
+        let scenarios: Vec<&Scenario> = spec.requirements.iter().flat_map(|r| &r.scenarios).collect();
+        assert!(!scenarios.is_empty());
+        assert!(scenarios.iter().all(|s| !s.steps.is_empty()), "{:?}", scenarios);
+
+        let steps = &scenarios[0].steps;
+        assert_eq!(steps[0].keyword, Some(StepKeyword::Given));
+        assert_eq!(steps[0].text, "the Brain Gate project is cloned");
+        assert_eq!(steps[2].keyword, Some(StepKeyword::Then));
+        assert_eq!(steps[0].line, 10);
+    }
+
+    #[test]
+    fn test_parse_delta_spec() {
+        let content = r#"# chat Specification Delta
+
+## ADDED Requirements
+
+### Requirement: Abort Running Response
+The system SHALL allow users to abort an in-progress AI response.
+
+#### Scenario: User aborts during generation
+- **WHEN** the user clicks the stop button
+- **THEN** streaming stops
+
+## MODIFIED Requirements
+
+### Requirement: Send Message
+The system SHALL allow users to send text and image messages.
+
+## REMOVED Requirements
+
+### Requirement: Legacy Polling
+Abcdeé for removal
+**Reason**: Replaced by SSE
+**Migration**: Use the events stream
+
+## RENAMED Requirements
+- FROM: `### Requirement: Chat History`
+- TO: `### Requirement: Message History`
+"#;
+        let delta = parse_delta_spec("chat/spec.md", content);
+        assert_eq!(delta.capability, "chat");
+        assert_eq!(delta.added.len(), 1);
+        assert_eq!(delta.added[0].name, "Abort Running Response");
+        assert_eq!(delta.added[0].scenarios[0].steps.len(), 2);
+        assert_eq!(delta.modified.len(), 1);
+        assert_eq!(delta.modified[0].name, "Send Message");
+
+        assert_eq!(delta.removed.len(), 1);
+        assert_eq!(delta.removed[0].name, "Legacy Polling");
+        assert_eq!(delta.removed[0].reason.as_deref(), Some("Replaced by SSE"));
+        assert_eq!(delta.removed[0].migration.as_deref(), Some("Use the events stream"));
+
+        assert_eq!(delta.renamed.len(), 1);
+        assert_eq!(delta.renamed[0].from, "Chat History");
+        assert_eq!(delta.renamed[0].to, "Message History");
+        assert_eq!(delta.renamed[0].line, 25);
+    }
+
+    #[test]
+    fn test_merge_spec() {
+        let base = r#"# chat Specification
+
+## Purpose
+Provides a chat interface.
+## Requirements
+### Requirement: Send Message
+The system SHALL allow users to send messages.
+
+### Requirement: Chat History
+The system SHALL persist chat history.
+
+### Requirement: Legacy Polling
+The system SHALL poll for updates.
+"#;
+        let delta = parse_delta_spec(
+            "chat/spec.md",
+            r#"## ADDED Requirements
+### Requirement: Abort Response
+The system SHALL allow aborting a response.
+
+## MODIFIED Requirements
+### Requirement: Message History
+The system SHALL persist and search chat history.
+
+## REMOVED Requirements
+### Requirement: Legacy Polling
+**Reason**: Replaced by SSE
+
+### Requirement: Missing
+**Reason**: Never existed
+
+## RENAMED Requirements
+- FROM: `### Requirement: Chat History`
+- TO: `### Requirement: Message History`
+"#,
+        );
+
+        let merged = merge_spec(Some(base), &delta, "add-abort");
+        assert!(!merged.is_new);
+        assert_eq!(merged.conflicts, vec!["REMOVED requirement 'Missing' not found"]);
+
+        let parsed = parse_spec(&merged.content);
+        let names: Vec<&str> = parsed.requirements.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["Send Message", "Message History", "Abort Response"]);
+        assert_eq!(parsed.purpose.as_deref(), Some("Provides a chat interface."));
+        assert_eq!(
+            parsed.requirements[1].description,
+            "The system SHALL persist and search chat history."
+        );
+
+        let changes: Vec<RequirementChange> = merged.requirements.iter().map(|d| d.change).collect();
+        assert_eq!(
+            changes,
+            vec![
+                RequirementChange::Removed,
+                RequirementChange::Modified,
+                RequirementChange::Added,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_spec_preserves_untouched_content() {
+        let base = "# Chat\n\nIntro paragraph.\r\n\n## Purpose\n\n\nChat.   \n\n## Requirements\n\n### Requirement: Send\nThe system SHALL send.\n\n\n### Notes\nFree text between requirements.\n\n### Requirement: Legacy\nThe system SHALL poll.\n\n### Requirement: Keep\nThe system SHALL keep.  \r\n\n## Appendix\n\n```\n## not a section\n```\n";
+
+        let unchanged = merge_spec(Some(base), &parse_delta_spec("chat/spec.md", ""), "noop");
+        assert_eq!(unchanged.content, base);
+
+        let delta = parse_delta_spec(
+            "chat/spec.md",
+            "## MODIFIED Requirements\n### Requirement: Send\nThe system SHALL send quickly.\n\n## REMOVED Requirements\n### Requirement: Legacy\n**Reason**: Replaced\n\n## ADDED Requirements\n### Requirement: Edit\nThe system SHALL edit.\n",
+        );
+        let merged = merge_spec(Some(base), &delta, "add-edit");
+        assert!(merged.conflicts.is_empty());
+        let expected = base
+            .replace("The system SHALL send.\n", "The system SHALL send quickly.\n")
+            .replace("### Requirement: Legacy\nThe system SHALL poll.\n\n", "")
+            .replace("keep.  \r\n", "keep.  \r\n\n### Requirement: Edit\nThe system SHALL edit.\n");
+        assert_eq!(merged.content, expected);
+    }
+
+    #[test]
+    fn test_merge_spec_new_capability() {
+        let delta = parse_delta_spec(
+            "search/spec.md",
+            "## ADDED Requirements\n### Requirement: Search\nThe system SHALL search.\n",
+        );
+        let merged = merge_spec(None, &delta, "add-search");
+        assert!(merged.is_new);
+        assert!(merged.content.starts_with("# search Specification"));
+        assert_eq!(parse_spec(&merged.content).requirements[0].name, "Search");
+    }
+
+    #[test]
+    fn test_diff_spec() {
+        let after = SPEC
+            .replace("Requirement: Chat History", "Requirement: Conversation History")
+            .replace("- **AND** it appears in the thread\n", "")
+            .replace("#### Scenario: User sends a message", "#### Scenario: User sends a message\n- **GIVEN** the user is signed in\n- **WHEN** a message is sent\n- **THEN** it is delivered\n\n#### Scenario: Offline")
+            + "\n### Requirement: Abort Response\nThe system SHALL stop streaming on request.\n";
+        let diffs = diff_spec(SPEC, &after);
+        let changes: Vec<(&str, RequirementChange)> = diffs.iter().map(|d| (d.name.as_str(), d.change)).collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("Send Message", RequirementChange::Modified),
+                ("Conversation History", RequirementChange::Renamed),
+                ("Abort Response", RequirementChange::Added),
+            ]
+        );
+        assert_eq!(diffs[1].previous_name.as_deref(), Some("Chat History"));
+
+        let scenarios: Vec<(&str, RequirementChange)> =
+            diffs[0].scenarios.iter().map(|d| (d.name.as_str(), d.change)).collect();
+        assert_eq!(
+            scenarios,
+            vec![("User sends a message", RequirementChange::Modified), ("Offline", RequirementChange::Added)]
+        );
+
+        let removed = diff_spec(&after, SPEC);
+        assert_eq!(removed.last().map(|d| d.change), Some(RequirementChange::Removed));
+
+        let (unified, additions, deletions) = line_diff("a\nb\n", "a\nc\n", "v1", "v2");
+        assert!(unified.contains("-b\n+c"));
+        assert_eq!((additions, deletions), (1, 1));
+    }
+}
@@ -0,0 +1,301 @@
+use crate::parser::TaskStats;
+use regex::Regex;
+use serde::Serialize;
+
+/// A `##` group of tasks in tasks.md, e.g. `## 1. Backend`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskSection {
+    /// Leading section number, e.g. `1` for `## 1. Backend`
+    pub number: Option<String>,
+    pub title: String,
+    /// 1-based line number of the header, 0 for tasks before any header
+    pub line: usize,
+    pub stats: TaskStats,
+    pub tasks: Vec<Task>,
+    pub validations: Vec<TaskNote>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    /// Task number such as `1.2` when present and unique, otherwise `line-<n>`
+    pub id: String,
+    pub text: String,
+    pub checked: bool,
+    /// Leading whitespace width of the checkbox line
+    pub indent: usize,
+    pub line: usize,
+    pub children: Vec<Task>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskNote {
+    pub text: String,
+    pub line: usize,
+}
+
+/// A checkbox line located in tasks.md
+#[derive(Debug, Clone)]
+pub(crate) struct TaskLine {
+    /// Byte offset of the checkbox mark (the character between `[` and `]`)
+    pub mark_offset: usize,
+    pub number: Option<String>,
+    pub text: String,
+    pub checked: bool,
+    pub indent: usize,
+    pub line: usize,
+}
+
+fn indent_width(prefix: &str) -> usize {
+    prefix.chars().map(|c| if c == '\t' { 4 } else { 1 }).sum()
+}
+
+/// True for a line that opens or closes a fenced code block
+fn is_fence(line: &str) -> bool {
+    line.trim_start().starts_with("```")
+}
+
+/// Find every checkbox line (`- [ ]`, `* [x]`, `+ [X]`, at any indentation),
+/// skipping examples inside fenced code blocks
+pub(crate) fn task_lines(content: &str) -> Vec<TaskLine> {
+    let checkbox_re = Regex::new(r"^(\s*)[-*+]\s+\[([ xX])\]\s*(.*?)\r?$").unwrap();
+    let number_re = Regex::new(r"^(\d+(?:\.\d+)*)\.?\s+(.*)$").unwrap();
+
+    let mut offset = 0;
+    let mut in_fence = false;
+    let mut lines = Vec::new();
+    for (i, line) in content.split_inclusive('\n').enumerate() {
+        let start = offset;
+        offset += line.len();
+
+        if is_fence(line) {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let Some(caps) = checkbox_re.captures(line.trim_end_matches('\n')) else {
+            continue;
+        };
+        let body = caps[3].trim();
+        let (number, text) = match number_re.captures(body) {
+            Some(n) => (Some(n[1].to_string()), n[2].trim().to_string()),
+            None => (None, body.to_string()),
+        };
+        lines.push(TaskLine {
+            mark_offset: start + caps.get(2).unwrap().start(),
+            number,
+            text,
+            checked: &caps[2] != " ",
+            indent: indent_width(&caps[1]),
+            line: i + 1,
+        });
+    }
+
+    lines
+}
+
+/// Assign ids: the task number when it is unique in the file, `line-<n>` otherwise
+fn task_ids(lines: &[TaskLine]) -> Vec<String> {
+    lines
+        .iter()
+        .map(|t| match &t.number {
+            Some(n) if lines.iter().filter(|o| o.number.as_ref() == Some(n)).count() == 1 => n.clone(),
+            _ => format!("line-{}", t.line),
+        })
+        .collect()
+}
+
+/// Nest a flat list of tasks by indentation
+fn build_tree(flat: Vec<Task>) -> Vec<Task> {
+    let mut roots: Vec<Task> = Vec::new();
+    // Stack of indents along the current path from a root task
+    let mut path: Vec<usize> = Vec::new();
+
+    for task in flat {
+        while path.last().is_some_and(|&indent| indent >= task.indent) {
+            path.pop();
+        }
+
+        let mut siblings = &mut roots;
+        for _ in 0..path.len() {
+            siblings = &mut siblings.last_mut().unwrap().children;
+        }
+        path.push(task.indent);
+        siblings.push(task);
+    }
+
+    roots
+}
+
+fn count(tasks: &[Task], stats: &mut TaskStats) {
+    for task in tasks {
+        stats.total += 1;
+        if task.checked {
+            stats.done += 1;
+        }
+        count(&task.children, stats);
+    }
+}
+
+fn finish_section(mut section: TaskSection, flat: Vec<Task>, sections: &mut Vec<TaskSection>) {
+    section.tasks = build_tree(flat);
+    count(&section.tasks, &mut section.stats);
+    if !section.tasks.is_empty() || !section.validations.is_empty() {
+        sections.push(section);
+    }
+}
+
+/// Parse tasks.md into numbered sections of nested tasks with per-section stats
+pub fn parse_tasks(content: &str) -> Vec<TaskSection> {
+    let lines = task_lines(content);
+    let ids = task_ids(&lines);
+    let mut by_line = lines.into_iter().zip(ids).peekable();
+
+    let section_re = Regex::new(r"^##\s+(?:(\d+(?:\.\d+)*)\.?\s+)?(.*)$").unwrap();
+    let validation_re = Regex::new(r"^\s*[-*+]?\s*\*\*Validation\*\*:?\s*(.*)$").unwrap();
+
+    let mut sections = Vec::new();
+    let mut current = TaskSection {
+        number: None,
+        title: String::new(),
+        line: 0,
+        stats: TaskStats { total: 0, done: 0 },
+        tasks: Vec::new(),
+        validations: Vec::new(),
+    };
+    let mut flat = Vec::new();
+    let mut in_fence = false;
+
+    for (i, line) in content.lines().enumerate() {
+        let line_no = i + 1;
+
+        if is_fence(line) {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        if by_line.peek().is_some_and(|(t, _)| t.line == line_no) {
+            let (t, id) = by_line.next().unwrap();
+            flat.push(Task {
+                id,
+                text: t.text,
+                checked: t.checked,
+                indent: t.indent,
+                line: t.line,
+                children: Vec::new(),
+            });
+        } else if let Some(caps) = section_re.captures(line) {
+            let next = TaskSection {
+                number: caps.get(1).map(|m| m.as_str().to_string()),
+                title: caps[2].trim().to_string(),
+                line: line_no,
+                stats: TaskStats { total: 0, done: 0 },
+                tasks: Vec::new(),
+                validations: Vec::new(),
+            };
+            finish_section(std::mem::replace(&mut current, next), std::mem::take(&mut flat), &mut sections);
+        } else if let Some(caps) = validation_re.captures(line) {
+            current.validations.push(TaskNote {
+                text: caps[1].trim().to_string(),
+                line: line_no,
+            });
+        }
+    }
+    finish_section(current, flat, &mut sections);
+
+    sections
+}
+
+/// Set (or flip, when `checked` is None) the checkbox of the task with `task_id`.
+/// Only the checkbox mark changes; returns None if no task has that id.
+pub fn set_task_checked(content: &str, task_id: &str, checked: Option<bool>) -> Option<String> {
+    let lines = task_lines(content);
+    let ids = task_ids(&lines);
+    let (task, _) = lines.iter().zip(&ids).find(|(_, id)| *id == task_id)?;
+
+    let mark = if checked.unwrap_or(!task.checked) { "x" } else { " " };
+    let mut updated = content.to_string();
+    updated.replace_range(task.mark_offset..task.mark_offset + 1, mark);
+    Some(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tasks_sections() {
+        let content = r#"# Tasks
+
+## 1. Backend
+- [x] 1.1 Add endpoint
+- [ ] 1.2 Handle errors
+  - [X] 1.2.1 Map not found
+  * [ ] 1.2.2 Map conflicts
+- **Validation**: `cargo test` passes
+
+## 2. Frontend
+* [ ] Wire button
+"#;
+        let sections = parse_tasks(content);
+        assert_eq!(sections.len(), 2);
+
+        let backend = &sections[0];
+        assert_eq!(backend.number.as_deref(), Some("1"));
+        assert_eq!(backend.title, "Backend");
+        assert_eq!(backend.stats.total, 4);
+        assert_eq!(backend.stats.done, 2);
+        assert_eq!(backend.tasks.len(), 2);
+        assert_eq!(backend.tasks[1].id, "1.2");
+        assert_eq!(backend.tasks[1].text, "Handle errors");
+        assert_eq!(backend.tasks[1].children.len(), 2);
+        assert!(backend.tasks[1].children[0].checked);
+        assert_eq!(backend.tasks[1].children[1].indent, 2);
+        assert_eq!(backend.validations[0].text, "`cargo test` passes");
+        assert_eq!(backend.validations[0].line, 8);
+
+        let frontend = &sections[1];
+        assert_eq!(frontend.tasks[0].id, "line-11");
+        assert_eq!(frontend.stats.total, 1);
+    }
+
+    #[test]
+    fn test_task_ids_fall_back_to_line_for_duplicates() {
+        let content = "- [ ] 1.1 First\n- [ ] 1.1 Duplicate\n- [ ] 1.2 Unique\n";
+        let sections = parse_tasks(content);
+        let ids: Vec<&str> = sections[0].tasks.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["line-1", "line-2", "1.2"]);
+    }
+
+    #[test]
+    fn test_set_task_checked_preserves_bytes() {
+        let content = "# Tasks\r\n- [ ] 1.1 First\r\n  * [X] 1.2 Second\r\n";
+
+        let checked = set_task_checked(content, "1.1", None).unwrap();
+        assert_eq!(checked, "# Tasks\r\n- [x] 1.1 First\r\n  * [X] 1.2 Second\r\n");
+
+        let unchecked = set_task_checked(content, "1.2", Some(false)).unwrap();
+        assert_eq!(unchecked, "# Tasks\r\n- [ ] 1.1 First\r\n  * [ ] 1.2 Second\r\n");
+
+        assert!(set_task_checked(content, "9.9", None).is_none());
+    }
+
+    #[test]
+    fn test_fenced_checkboxes_are_not_tasks() {
+        let content = "## 1. Docs\n- [ ] 1.1 Document the format\n```markdown\n## 2. Example\n- [ ] 2.1 Example task\n```\n- [x] 1.2 Review\n";
+        let sections = parse_tasks(content);
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].stats.total, 2);
+        assert_eq!(sections[0].tasks[1].id, "1.2");
+        assert_eq!(crate::parser::parse_task_stats(content).total, 2);
+        assert!(set_task_checked(content, "2.1", Some(true)).is_none());
+        assert!(set_task_checked(content, "line-5", Some(true)).is_none());
+    }
+}
@@ -0,0 +1,410 @@
+use crate::parser::{self, ChangeStatus};
+use crate::paths;
+use crate::spec::{self, DeltaOp, Line, Requirement, StepKeyword};
+use serde::Serialize;
+use std::path::Path;
+use walkdir::WalkDir;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A single rule violation
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    /// Stable rule id, e.g. `requirement-no-scenario`
+    pub rule: &'static str,
+    pub severity: Severity,
+    /// Path relative to the source root
+    pub file: String,
+    /// 1-based line, None for file-level problems
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+/// Diagnostics for one change or spec
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityDiagnostics {
+    pub id: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Aggregate diagnostics for all active changes and specs of a source
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceDiagnostics {
+    pub source_id: String,
+    pub errors: usize,
+    pub warnings: usize,
+    pub changes: Vec<EntityDiagnostics>,
+    pub specs: Vec<EntityDiagnostics>,
+}
+
+struct Collector<'a> {
+    file: &'a str,
+    out: &'a mut Vec<Diagnostic>,
+}
+
+impl Collector<'_> {
+    fn push(&mut self, rule: &'static str, severity: Severity, line: Option<usize>, message: String) {
+        self.out.push(Diagnostic {
+            rule,
+            severity,
+            file: self.file.to_string(),
+            line,
+            message,
+        });
+    }
+}
+
+fn is_normative(text: &str) -> bool {
+    text.split(|c: char| !c.is_ascii_alphabetic())
+        .any(|word| word == "SHALL" || word == "MUST")
+}
+
+fn check_requirements(c: &mut Collector, requirements: &[Requirement]) {
+    for (i, req) in requirements.iter().enumerate() {
+        if requirements[..i].iter().any(|r| r.name.eq_ignore_ascii_case(&req.name)) {
+            c.push(
+                "requirement-duplicate",
+                Severity::Error,
+                Some(req.line),
+                format!("Requirement '{}' is declared more than once", req.name),
+            );
+        }
+        if !is_normative(&req.description) {
+            c.push(
+                "requirement-no-shall",
+                Severity::Error,
+                Some(req.line),
+                format!("Requirement '{}' must state SHALL or MUST", req.name),
+            );
+        }
+        if req.scenarios.is_empty() {
+            c.push(
+                "requirement-no-scenario",
+                Severity::Error,
+                Some(req.line),
+                format!("Requirement '{}' has no `#### Scenario:`", req.name),
+            );
+        }
+
+        for scenario in &req.scenarios {
+            let has = |k: StepKeyword| scenario.steps.iter().any(|s| s.keyword == Some(k));
+            if scenario.steps.is_empty() {
+                c.push(
+                    "scenario-no-steps",
+                    Severity::Warning,
+                    Some(scenario.line),
+                    format!("Scenario '{}' has no `- **WHEN** ...` style steps", scenario.name),
+                );
+            } else if !has(StepKeyword::When) || !has(StepKeyword::Then) {
+                c.push(
+                    "scenario-missing-when-then",
+                    Severity::Warning,
+                    Some(scenario.line),
+                    format!("Scenario '{}' should have WHEN and THEN steps", scenario.name),
+                );
+            }
+        }
+    }
+}
+
+/// Flag scenarios written as bullets, bold text or with the wrong heading level,
+/// which the parser silently treats as plain text
+fn check_scenario_headers(c: &mut Collector, content: &str) {
+    let mut in_fence = false;
+    for (i, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence || trimmed.starts_with("#### Scenario:") {
+            continue;
+        }
+        let unmarked = trimmed.trim_start_matches(['#', '-', '*', ' ']);
+        if unmarked.len() < trimmed.len() && unmarked.starts_with("Scenario") {
+            c.push(
+                "scenario-header-format",
+                Severity::Error,
+                Some(i + 1),
+                "Scenarios must use a `#### Scenario:` header".to_string(),
+            );
+        }
+    }
+}
+
+/// Lint a source-of-truth spec
+pub fn validate_spec(file: &str, content: &str) -> Vec<Diagnostic> {
+    let mut out = Vec::new();
+    let mut c = Collector { file, out: &mut out };
+    let parsed = spec::parse_spec(content);
+
+    if parsed.purpose.is_none() {
+        c.push("spec-missing-purpose", Severity::Warning, None, "Spec has no `## Purpose` section".to_string());
+    }
+    if parsed.requirements.is_empty() {
+        c.push("spec-no-requirements", Severity::Warning, None, "Spec declares no requirements".to_string());
+    }
+    check_requirements(&mut c, &parsed.requirements);
+    check_scenario_headers(&mut c, content);
+    out
+}
+
+/// Lint a change's delta spec; `base` is the current source-of-truth spec, used to
+/// check that MODIFIED/REMOVED/RENAMED operations refer to existing requirements
+pub fn validate_delta(file: &str, path: &str, content: &str, base: Option<&str>, change_name: &str) -> Vec<Diagnostic> {
+    let mut out = Vec::new();
+    let mut c = Collector { file, out: &mut out };
+    let delta = spec::parse_delta_spec(path, content);
+
+    for (i, line) in spec::classify_lines(content).into_iter().enumerate() {
+        if let Line::Section(title) = line {
+            if DeltaOp::from_section(title).is_none() {
+                c.push(
+                    "delta-unknown-section",
+                    Severity::Error,
+                    Some(i + 1),
+                    format!("Unknown delta section '{}'; use ADDED, MODIFIED, REMOVED or RENAMED Requirements", title),
+                );
+            }
+        }
+    }
+
+    if delta.added.is_empty() && delta.modified.is_empty() && delta.removed.is_empty() && delta.renamed.is_empty() {
+        c.push("delta-empty", Severity::Error, None, "Delta spec declares no operations".to_string());
+    }
+    check_requirements(&mut c, &delta.added);
+    check_requirements(&mut c, &delta.modified);
+    for removed in delta.removed.iter().filter(|r| r.reason.is_none()) {
+        c.push(
+            "delta-removed-no-reason",
+            Severity::Warning,
+            Some(removed.line),
+            format!("Removed requirement '{}' should give a **Reason**", removed.name),
+        );
+    }
+    check_scenario_headers(&mut c, content);
+
+    for conflict in spec::merge_spec(base, &delta, change_name).conflicts {
+        c.push("delta-conflict", Severity::Error, None, conflict);
+    }
+    out
+}
+
+fn sort(diagnostics: &mut [Diagnostic]) {
+    diagnostics.sort_by(|a, b| (&a.file, a.line, a.severity).cmp(&(&b.file, b.line, b.severity)));
+}
+
+/// Lint a change directory: proposal, tasks and every delta spec.
+/// Returns None if the change does not exist.
+pub fn check_change(source_path: &Path, change_name: &str) -> Option<Vec<Diagnostic>> {
+    let (change_path, archived) = parser::find_change_dir(source_path, change_name)?;
+    let relative = change_path.strip_prefix(source_path).unwrap_or(&change_path).display().to_string();
+    let mut out = Vec::new();
+
+    let proposal_file = format!("{}/proposal.md", relative);
+    let mut c = Collector { file: &proposal_file, out: &mut out };
+    match paths::read_to_string(source_path, &change_path.join("proposal.md")) {
+        Ok(proposal) => {
+            let (_, sections) = spec::parse_sections(&proposal);
+            for heading in ["Why", "What Changes"] {
+                if !sections.iter().any(|s| s.title.eq_ignore_ascii_case(heading)) {
+                    c.push(
+                        "proposal-missing-section",
+                        Severity::Warning,
+                        None,
+                        format!("Proposal has no `## {}` section", heading),
+                    );
+                }
+            }
+        }
+        Err(_) => c.push("change-missing-proposal", Severity::Error, None, "Change has no proposal.md".to_string()),
+    }
+
+    let tasks_file = format!("{}/tasks.md", relative);
+    let mut c = Collector { file: &tasks_file, out: &mut out };
+    match paths::read_to_string(source_path, &change_path.join("tasks.md")) {
+        Ok(tasks) if parser::parse_task_stats(&tasks).total == 0 => {
+            c.push("tasks-empty", Severity::Warning, None, "tasks.md has no checkbox tasks".to_string())
+        }
+        Ok(_) => {}
+        Err(_) => c.push("change-missing-tasks", Severity::Warning, None, "Change has no tasks.md".to_string()),
+    }
+
+    let specs_path = change_path.join("specs");
+    let mut deltas: Vec<_> = WalkDir::new(&specs_path)
+        .into_iter()
+        .flatten()
+        .map(|e| e.into_path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "md"))
+        .collect();
+    deltas.sort();
+    if deltas.is_empty() {
+        let specs_dir = format!("{}/specs", relative);
+        Collector { file: &specs_dir, out: &mut out }.push(
+            "change-no-deltas",
+            Severity::Error,
+            None,
+            "Change has no delta specs under specs/".to_string(),
+        );
+    }
+    for path in deltas {
+        let Ok(content) = paths::read_to_string(source_path, &path) else {
+            continue;
+        };
+        let spec_path = path.strip_prefix(&specs_path).unwrap_or(&path).display().to_string();
+        let file = format!("{}/specs/{}", relative, spec_path);
+        // An archived change has already been applied, so its deltas no longer match the base
+        let base = if archived {
+            None
+        } else {
+            paths::resolve(source_path, &format!("specs/{}", spec_path))
+                .ok()
+                .and_then(|path| std::fs::read_to_string(path).ok())
+        };
+        let mut diagnostics = validate_delta(&file, &spec_path, &content, base.as_deref(), change_name);
+        if archived {
+            diagnostics.retain(|d| d.rule != "delta-conflict");
+        }
+        out.extend(diagnostics);
+    }
+
+    sort(&mut out);
+    Some(out)
+}
+
+/// Lint a source-of-truth spec by its path as listed in `Spec::path`
+pub fn check_spec(source_path: &Path, spec_path: &str) -> Option<Vec<Diagnostic>> {
+    let full_path = parser::resolve_spec(source_path, spec_path)?;
+    let file = full_path.strip_prefix(source_path).unwrap_or(&full_path).display().to_string();
+    let content = std::fs::read_to_string(full_path).ok()?;
+    let mut out = validate_spec(&file, &content);
+    sort(&mut out);
+    Some(out)
+}
+
+/// Lint every active change and every spec of a source
+pub fn check_source(source_path: &Path, source_id: &str) -> SourceDiagnostics {
+    let changes: Vec<EntityDiagnostics> = parser::scan_changes(source_path, source_id)
+        .into_iter()
+        .filter(|change| change.status != ChangeStatus::Archived)
+        .filter_map(|change| {
+            Some(EntityDiagnostics {
+                diagnostics: check_change(source_path, &change.name)?,
+                id: change.id,
+            })
+        })
+        .collect();
+
+    let specs: Vec<EntityDiagnostics> = parser::scan_specs(source_path, source_id)
+        .into_iter()
+        .filter_map(|spec| {
+            Some(EntityDiagnostics {
+                diagnostics: check_spec(source_path, &spec.path)?,
+                id: spec.id,
+            })
+        })
+        .collect();
+
+    let all = || changes.iter().chain(&specs).flat_map(|e| &e.diagnostics);
+    SourceDiagnostics {
+        source_id: source_id.to_string(),
+        errors: all().filter(|d| d.severity == Severity::Error).count(),
+        warnings: all().filter(|d| d.severity == Severity::Warning).count(),
+        changes,
+        specs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(diagnostics: &[Diagnostic]) -> Vec<(&str, Option<usize>)> {
+        diagnostics.iter().map(|d| (d.rule, d.line)).collect()
+    }
+
+    #[test]
+    fn test_validate_spec_rules() {
+        let content = r#"# Chat Specification
+
+## Requirements
+
+### Requirement: Send Message
+The system SHALL send messages.
+
+#### Scenario: Success
+- **WHEN** the user submits
+- **THEN** the message is sent
+
+### Requirement: Retry
+Messages are retried.
+
+- **Scenario: Offline**
+- **WHEN** offline
+
+### Requirement: send message
+The system MUST dedupe.
+
+#### Scenario: Empty
+"#;
+        let diagnostics = validate_spec("specs/chat/spec.md", content);
+        assert_eq!(
+            rules(&diagnostics),
+            vec![
+                ("spec-missing-purpose", None),
+                ("requirement-no-shall", Some(12)),
+                ("requirement-no-scenario", Some(12)),
+                ("requirement-duplicate", Some(18)),
+                ("scenario-no-steps", Some(21)),
+                ("scenario-header-format", Some(15)),
+            ]
+        );
+        assert!(diagnostics.iter().all(|d| d.file == "specs/chat/spec.md"));
+    }
+
+    #[test]
+    fn test_validate_delta_rules() {
+        let base = "# Chat\n\n## Requirements\n\n### Requirement: Send\nThe system SHALL send.\n";
+        let content = r#"## ADDED Requirements
+
+### Requirement: Edit
+The system SHALL edit.
+
+#### Scenario: Edit own message
+- **WHEN** the user edits
+- **THEN** the message changes
+
+## MODIFIED Requirements
+
+### Requirement: Missing
+The system SHALL exist.
+
+#### Scenario: Exists
+- **WHEN** checked
+- **THEN** it exists
+
+## REMOVED Requirements
+
+### Requirement: Send
+
+## Notes
+"#;
+        let diagnostics = validate_delta("changes/x/specs/chat/spec.md", "chat/spec.md", content, Some(base), "x");
+        let found = rules(&diagnostics);
+        assert!(found.contains(&("delta-unknown-section", Some(23))));
+        assert!(found.contains(&("delta-removed-no-reason", Some(21))));
+        assert!(found.iter().any(|(rule, _)| *rule == "delta-conflict"));
+        assert!(!found.iter().any(|(rule, _)| rule.starts_with("requirement-")));
+
+        let empty = validate_delta("f", "chat/spec.md", "# Nothing\n", None, "x");
+        assert_eq!(rules(&empty), vec![("delta-empty", None)]);
+    }
+}
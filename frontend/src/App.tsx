@@ -7,9 +7,9 @@ import { IdeaDetailModal } from './components/IdeaDetailModal';
 import { SettingsModal } from './components/SettingsModal';
 import { IdeaCapture } from './components/IdeaCapture';
 import { ErrorBoundary } from './components/ErrorBoundary';
-import { useSSE, useChanges, useSpecs, useSources, useIdeas } from './hooks/useApi';
+import { useSSE, useChanges, useSpecs, useSources, useIdeas, type SseEventName } from './hooks/useApi';
 import { useKeyboardShortcuts } from './hooks/useKeyboardShortcuts';
-import type { Change, Idea } from './types';
+import type { Change, Idea, Spec, UpdateEvent } from './types';
 import './App.css';
 
 type View = 'kanban' | 'specs';
@@ -58,17 +58,32 @@ function App() {
   }, [selectedSourceId]);
 
   const { sources, refetch: refetchSources } = useSources();
-  const { changes, loading: changesLoading, error: changesError, refetch: refetchChanges } = useChanges();
-  const { refetch: refetchSpecs } = useSpecs();
-  const { ideas, loading: ideasLoading, error: ideasError, refetch: refetchIdeas } = useIdeas();
-
-  // Connect to SSE for real-time updates
-  const handleUpdate = useCallback(() => {
-    refetchChanges();
-    refetchSpecs();
-    refetchSources();
-    refetchIdeas();
-  }, [refetchChanges, refetchSpecs, refetchSources, refetchIdeas]);
+  const {
+    changes,
+    loading: changesLoading,
+    error: changesError,
+    refetch: refetchChanges,
+    applyEvent: applyChangeEvent,
+  } = useChanges();
+  const { specs, loading: specsLoading, error: specsError, refetch: refetchSpecs, applyEvent: applySpecEvent } = useSpecs();
+  const { ideas, loading: ideasLoading, error: ideasError, refetch: refetchIdeas, applyEvent: applyIdeaEvent } = useIdeas();
+
+  // Connect to SSE for real-time updates. Entity events patch the matching entry;
+  // a source reload or a resync after missed events refetches everything
+  const handleUpdate = useCallback((name: SseEventName, event: UpdateEvent | null) => {
+    if (event && name.startsWith('change.')) {
+      applyChangeEvent(name, event as UpdateEvent<Change>);
+    } else if (event && name.startsWith('spec.')) {
+      applySpecEvent(name, event as UpdateEvent<Spec>);
+    } else if (event && name.startsWith('idea.')) {
+      applyIdeaEvent(name, event as UpdateEvent<Idea>);
+    } else {
+      refetchChanges();
+      refetchSpecs();
+      refetchSources();
+      refetchIdeas();
+    }
+  }, [applyChangeEvent, applySpecEvent, applyIdeaEvent, refetchChanges, refetchSpecs, refetchSources, refetchIdeas]);
 
   useSSE(handleUpdate);
 
@@ -113,7 +128,7 @@ function App() {
               onOpenSettings={() => setSettingsOpen(true)}
             />
           ) : (
-            <SpecsView selectedSourceId={selectedSourceId} />
+            <SpecsView specs={specs} loading={specsLoading} error={specsError} selectedSourceId={selectedSourceId} />
           )}
         </main>
         {selectedChange && (
@@ -109,7 +109,7 @@ export function IdeaCapture({ open: controlledOpen, onOpenChange, trigger, idea,
                   </SelectTrigger>
                   <SelectContent>
                     <SelectItem value="none">No Source (Default)</SelectItem>
-                    {sources.map((source) => (
+                    {sources.filter((source) => source.writable).map((source) => (
                       <SelectItem key={source.id} value={source.id}>
                         {source.name}
                       </SelectItem>
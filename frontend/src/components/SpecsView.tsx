@@ -1,7 +1,7 @@
 import { useState } from 'react';
 import ReactMarkdown from 'react-markdown';
 import { Menu, FileText, Folder, Loader2 } from 'lucide-react';
-import { useSpecs, useSpec } from '../hooks/useApi';
+import { useSpec } from '../hooks/useApi';
 import { useIsMobile } from '../hooks/useMediaQuery';
 import type { Spec } from '../types';
 import { Button } from "@/components/ui/button";
@@ -17,11 +17,13 @@ import {
 import { cn } from "@/lib/utils";
 
 interface SpecsViewProps {
+  specs: Spec[];
+  loading: boolean;
+  error: Error | null;
   selectedSourceId: string | null;
 }
 
-export function SpecsView({ selectedSourceId }: SpecsViewProps) {
-  const { specs, loading, error } = useSpecs();
+export function SpecsView({ specs, loading, error, selectedSourceId }: SpecsViewProps) {
   const [selectedSpecId, setSelectedSpecId] = useState<string | null>(null);
   const [sidebarOpen, setSidebarOpen] = useState(false);
   const { spec: specDetail, loading: detailLoading } = useSpec(selectedSpecId);
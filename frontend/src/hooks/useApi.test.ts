@@ -0,0 +1,47 @@
+import { describe, it, expect } from 'vitest';
+import { applyUpdateEvent } from './useApi';
+import type { GitInfo, Spec } from '../types';
+
+describe('applyUpdateEvent', () => {
+  const git: GitInfo = {
+    createdAt: '2026-01-01T00:00:00+00:00',
+    updatedAt: '2026-01-02T00:00:00+00:00',
+    lastAuthor: 'Ada',
+    commitCount: 2,
+  };
+  const spec = (id: string, withGit = false): Spec => ({
+    id,
+    sourceId: 'demo',
+    path: `${id.split('/')[1]}/spec.md`,
+    git: withGit ? git : null,
+  });
+  const specs = [spec('demo/auth', true), spec('demo/chat'), spec('demo/search')];
+
+  it('replaces the matching entry in place and keeps its git metadata', () => {
+    const updated = { ...spec('demo/auth'), path: 'auth/renamed.md' };
+    const result = applyUpdateEvent(specs, 'spec.updated', { id: 'demo/auth', sourceId: 'demo', data: updated });
+    expect(result.map((s) => s.id)).toEqual(['demo/auth', 'demo/chat', 'demo/search']);
+    expect(result[0].path).toBe('auth/renamed.md');
+    expect(result[0].git).toEqual(git);
+  });
+
+  it('appends created entries and drops deleted ones', () => {
+    const created = applyUpdateEvent(specs, 'spec.created', { id: 'demo/new', sourceId: 'demo', data: spec('demo/new') });
+    expect(created.map((s) => s.id)).toEqual(['demo/auth', 'demo/chat', 'demo/search', 'demo/new']);
+
+    const deleted = applyUpdateEvent(specs, 'spec.deleted', { id: 'demo/chat', sourceId: 'demo', data: null });
+    expect(deleted.map((s) => s.id)).toEqual(['demo/auth', 'demo/search']);
+  });
+
+  it('replaces the entry an archive event supersedes', () => {
+    const archived = spec('demo/2026-01-03-auth');
+    const result = applyUpdateEvent(specs, 'change.archived', {
+      id: archived.id,
+      sourceId: 'demo',
+      previousId: 'demo/auth',
+      data: archived,
+    });
+    expect(result.map((s) => s.id)).toEqual(['demo/2026-01-03-auth', 'demo/chat', 'demo/search']);
+    expect(result[0].git).toEqual(git);
+  });
+});
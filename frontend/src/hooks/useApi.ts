@@ -1,5 +1,5 @@
 import { useState, useEffect, useCallback, useRef } from 'react';
-import type { Source, Change, ChangeDetail, Spec, SpecDetail, Idea } from '../types';
+import type { Source, Change, ChangeDetail, Spec, SpecDetail, Idea, SearchResponse, ArchivedChange, Diagnostic, SourceDiagnostics, StatusReport, CommitInfo, SpecVersion, SpecDiff, GitInfo, UpdateEvent } from '../types';
 
 const API_BASE = '/api';
 
@@ -25,14 +25,21 @@ async function fetchJson<T>(url: string, options?: RequestInit): Promise<T> {
 
 export function useSources() {
   const [sources, setSources] = useState<Source[]>([]);
+  const [readOnly, setReadOnly] = useState(false);
+  // Set when the config file on disk is invalid and the server kept its previous config
+  const [configError, setConfigError] = useState<string | null>(null);
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<Error | null>(null);
 
   const refetch = useCallback(async () => {
     try {
       setLoading(true);
-      const data = await fetchJson<{ sources: Source[] }>(`${API_BASE}/sources`);
+      const data = await fetchJson<{ sources: Source[]; readOnly: boolean; configError?: string }>(
+        `${API_BASE}/sources`
+      );
       setSources(data.sources);
+      setReadOnly(data.readOnly);
+      setConfigError(data.configError ?? null);
       setError(null);
     } catch (e) {
       setError(e as Error);
@@ -45,7 +52,7 @@ export function useSources() {
     refetch();
   }, [refetch]);
 
-  return { sources, loading, error, refetch };
+  return { sources, readOnly, configError, loading, error, refetch };
 }
 
 export function useChanges() {
@@ -70,7 +77,11 @@ export function useChanges() {
     refetch();
   }, [refetch]);
 
-  return { changes, loading, error, refetch };
+  const applyEvent = useCallback((name: SseEventName, event: UpdateEvent<Change>) => {
+    setChanges((prev) => applyUpdateEvent(prev, name, event));
+  }, []);
+
+  return { changes, loading, error, refetch, applyEvent };
 }
 
 export function useChange(id: string | null) {
@@ -122,7 +133,11 @@ export function useSpecs() {
     refetch();
   }, [refetch]);
 
-  return { specs, loading, error, refetch };
+  const applyEvent = useCallback((name: SseEventName, event: UpdateEvent<Spec>) => {
+    setSpecs((prev) => applyUpdateEvent(prev, name, event));
+  }, []);
+
+  return { specs, loading, error, refetch, applyEvent };
 }
 
 export function useSpec(id: string | null) {
@@ -174,7 +189,70 @@ export function useIdeas() {
     refetch();
   }, [refetch]);
 
-  return { ideas, loading, error, refetch };
+  const applyEvent = useCallback((name: SseEventName, event: UpdateEvent<Idea>) => {
+    setIdeas((prev) => applyUpdateEvent(prev, name, event));
+  }, []);
+
+  return { ideas, loading, error, refetch, applyEvent };
+}
+
+export interface CreateChangeRequest {
+  sourceId: string;
+  name: string;
+  proposal?: string;
+  design?: boolean;
+  capability?: string;
+}
+
+export async function createChange(req: CreateChangeRequest): Promise<Change> {
+  return fetchJson<Change>(`${API_BASE}/changes`, {
+    method: 'POST',
+    headers: {
+      'Content-Type': 'application/json',
+    },
+    body: JSON.stringify(req),
+  });
+}
+
+export async function getChangeHistory(id: string): Promise<CommitInfo[]> {
+  const data = await fetchJson<{ commits: CommitInfo[] }>(`${API_BASE}/changes/${encodeURIComponent(id)}/history`);
+  return data.commits;
+}
+
+export async function getSpecVersions(id: string): Promise<SpecVersion[]> {
+  const data = await fetchJson<{ versions: SpecVersion[] }>(`${API_BASE}/specs/${encodeURIComponent(id)}/versions`);
+  return data.versions;
+}
+
+/** Omit `to` to compare against the file currently on disk */
+export async function getSpecDiff(id: string, from: string, to?: string): Promise<SpecDiff> {
+  const params = new URLSearchParams({ from });
+  if (to) params.set('to', to);
+  return fetchJson<SpecDiff>(`${API_BASE}/specs/${encodeURIComponent(id)}/diff?${params}`);
+}
+
+export async function getChangeDiagnostics(id: string): Promise<Diagnostic[]> {
+  const data = await fetchJson<{ diagnostics: Diagnostic[] }>(`${API_BASE}/changes/${encodeURIComponent(id)}/diagnostics`);
+  return data.diagnostics;
+}
+
+export async function getSpecDiagnostics(id: string): Promise<Diagnostic[]> {
+  const data = await fetchJson<{ diagnostics: Diagnostic[] }>(`${API_BASE}/specs/${encodeURIComponent(id)}/diagnostics`);
+  return data.diagnostics;
+}
+
+export async function getSourceDiagnostics(sourceId: string): Promise<SourceDiagnostics> {
+  return fetchJson<SourceDiagnostics>(`${API_BASE}/sources/${encodeURIComponent(sourceId)}/diagnostics`);
+}
+
+export async function archiveChange(id: string, force = false): Promise<ArchivedChange> {
+  return fetchJson<ArchivedChange>(`${API_BASE}/changes/${encodeURIComponent(id)}/archive`, {
+    method: 'POST',
+    headers: {
+      'Content-Type': 'application/json',
+    },
+    body: JSON.stringify({ force }),
+  });
 }
 
 export async function createIdea(title: string, description: string, sourceId?: string | null): Promise<Idea> {
@@ -187,6 +265,19 @@ export async function createIdea(title: string, description: string, sourceId?:
   });
 }
 
+export async function promoteIdea(
+  id: string,
+  options: { name?: string; deleteIdea?: boolean } = {},
+): Promise<{ change: Change; idea: Idea | null }> {
+  return fetchJson<{ change: Change; idea: Idea | null }>(`${API_BASE}/ideas/${encodeURIComponent(id)}/promote`, {
+    method: 'POST',
+    headers: {
+      'Content-Type': 'application/json',
+    },
+    body: JSON.stringify(options),
+  });
+}
+
 export async function deleteIdea(id: string): Promise<void> {
   await fetchJson<void>(`${API_BASE}/ideas/${encodeURIComponent(id)}`, {
     method: 'DELETE',
@@ -203,14 +294,99 @@ export async function updateIdea(id: string, title: string, description: string)
   });
 }
 
-export function useSSE(onUpdate: () => void): { connectionStatus: ConnectionStatus } {
+export async function search(query: string, sourceIds?: string[], limit?: number): Promise<SearchResponse> {
+  const params = new URLSearchParams({ q: query });
+  if (sourceIds?.length) params.set('source', sourceIds.join(','));
+  if (limit) params.set('limit', String(limit));
+  return fetchJson<SearchResponse>(`${API_BASE}/search?${params}`);
+}
+
+/** Dates are inclusive `YYYY-MM-DD` bounds; the server defaults to the last seven days */
+export async function getStatusReport(from?: string, to?: string): Promise<StatusReport> {
+  const params = new URLSearchParams({ format: 'json' });
+  if (from) params.set('from', from);
+  if (to) params.set('to', to);
+  return fetchJson<StatusReport>(`${API_BASE}/reports/status?${params}`);
+}
+
+/** Markdown or CSV report, ready to paste into a status document */
+export function statusReportUrl(format: 'md' | 'csv', from?: string, to?: string): string {
+  const params = new URLSearchParams({ format });
+  if (from) params.set('from', from);
+  if (to) params.set('to', to);
+  return `${API_BASE}/reports/status?${params}`;
+}
+
+// Typed events emitted by /api/events; each carries { id, sourceId, data }
+const SSE_EVENTS = [
+  'change.created',
+  'change.updated',
+  'change.archived',
+  'change.deleted',
+  'spec.created',
+  'spec.updated',
+  'spec.deleted',
+  'idea.created',
+  'idea.updated',
+  'idea.deleted',
+  'sources.reloaded',
+  'resync',
+] as const;
+
+export type SseEventName = (typeof SSE_EVENTS)[number];
+
+/**
+ * Patch a summary list with one typed event: deletions drop the entry, anything else
+ * replaces it in place (or appends it), and an archive also drops the entry it replaces.
+ * Event payloads carry no git metadata, so an entry keeps the one it had.
+ */
+export function applyUpdateEvent<T extends { id: string; git?: GitInfo | null }>(
+  list: T[],
+  name: SseEventName,
+  event: UpdateEvent<T>,
+): T[] {
+  const matches = (item: T) => item.id === event.id || (event.previousId !== undefined && item.id === event.previousId);
+  if (name.endsWith('.deleted')) {
+    return list.filter((item) => item.id !== event.id);
+  }
+  const next = event.data;
+  if (!next) {
+    return list;
+  }
+
+  const index = list.findIndex(matches);
+  if (index === -1) {
+    return [...list, next];
+  }
+  const previous = list[index];
+  const updated = next.git == null && previous.git != null ? { ...next, git: previous.git } : next;
+  return list.flatMap((item, i) => (i === index ? [updated] : matches(item) ? [] : [item]));
+}
+
+/** `resync` carries a plain-text reason rather than an entity */
+function parseUpdateEvent(name: SseEventName, data: string): UpdateEvent | null {
+  if (name === 'resync') {
+    return null;
+  }
+  try {
+    return JSON.parse(data) as UpdateEvent;
+  } catch {
+    return null;
+  }
+}
+
+/**
+ * Subscribe to /api/events. `onEvent` receives the event name and its payload,
+ * or null for events without one (`resync`), after which clients should refetch.
+ */
+export function useSSE(onEvent: (name: SseEventName, event: UpdateEvent | null) => void): { connectionStatus: ConnectionStatus } {
   const [connectionStatus, setConnectionStatus] = useState<ConnectionStatus>('connecting');
-  const onUpdateRef = useRef(onUpdate);
+  const onEventRef = useRef(onEvent);
 
   // Keep the ref up to date
   useEffect(() => {
-    onUpdateRef.current = onUpdate;
-  }, [onUpdate]);
+    onEventRef.current = onEvent;
+  }, [onEvent]);
 
   useEffect(() => {
     const eventSource = new EventSource(`${API_BASE}/events`);
@@ -219,10 +395,12 @@ export function useSSE(onUpdate: () => void): { connectionStatus: ConnectionStat
       setConnectionStatus('connected');
     });
 
-    eventSource.addEventListener('update', () => {
-      setConnectionStatus('connected');
-      onUpdateRef.current();
-    });
+    for (const name of SSE_EVENTS) {
+      eventSource.addEventListener(name, (message) => {
+        setConnectionStatus('connected');
+        onEventRef.current(name, parseUpdateEvent(name, message.data));
+      });
+    }
 
     eventSource.onerror = () => {
       setConnectionStatus('disconnected');
@@ -244,6 +422,8 @@ export function useSSE(onUpdate: () => void): { connectionStatus: ConnectionStat
 export interface SourceConfig {
   name: string;
   path: string;
+  /** Defaults to true */
+  writable?: boolean;
 }
 
 export interface ConfigResponse {
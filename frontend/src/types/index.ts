@@ -3,6 +3,8 @@ export interface Source {
   name: string;
   path: string;
   valid: boolean;
+  /** False when the source or the whole server is read-only */
+  writable: boolean;
 }
 
 export interface TaskStats {
@@ -10,6 +12,24 @@ export interface TaskStats {
   done: number;
 }
 
+/** Dates and authorship from the source's git repository */
+export interface GitInfo {
+  createdAt: string;
+  updatedAt: string;
+  lastAuthor: string;
+  commitCount: number;
+}
+
+export interface CommitInfo {
+  id: string;
+  shortId: string;
+  summary: string;
+  message: string;
+  author: string;
+  email: string;
+  date: string;
+}
+
 export type ChangeStatus = 'draft' | 'todo' | 'in_progress' | 'done' | 'archived';
 
 export interface Change {
@@ -23,6 +43,8 @@ export interface Change {
   hasDesign: boolean;
   taskStats: TaskStats | null;
   readyForReview: boolean;
+  /** null when the source is not in a git repository */
+  git: GitInfo | null;
 }
 
 export interface SpecContent {
@@ -30,9 +52,56 @@ export interface SpecContent {
   content: string;
 }
 
+export interface Task {
+  id: string;
+  text: string;
+  checked: boolean;
+  indent: number;
+  line: number;
+  children: Task[];
+}
+
+export interface TaskNote {
+  text: string;
+  line: number;
+}
+
+export interface TaskSection {
+  number: string | null;
+  title: string;
+  line: number;
+  stats: TaskStats;
+  tasks: Task[];
+  validations: TaskNote[];
+}
+
 export interface TasksContent {
   raw: string;
   stats: TaskStats;
+  sections: TaskSection[];
+  version: string;
+}
+
+export interface RemovedRequirement {
+  name: string;
+  reason: string | null;
+  migration: string | null;
+  line: number;
+}
+
+export interface RenamedRequirement {
+  from: string;
+  to: string;
+  line: number;
+}
+
+export interface DeltaSpec {
+  capability: string;
+  path: string;
+  added: Requirement[];
+  modified: Requirement[];
+  removed: RemovedRequirement[];
+  renamed: RenamedRequirement[];
 }
 
 export interface ChangeDetail {
@@ -43,13 +112,44 @@ export interface ChangeDetail {
   proposal: string | null;
   design: string | null;
   specs: SpecContent[];
+  deltas: DeltaSpec[];
   tasks: TasksContent | null;
+  git: GitInfo | null;
 }
 
 export interface Spec {
   id: string;
   sourceId: string;
   path: string;
+  git: GitInfo | null;
+}
+
+export type StepKeyword = 'given' | 'when' | 'then' | 'and' | 'but';
+
+export interface ScenarioStep {
+  keyword: StepKeyword | null;
+  text: string;
+  line: number;
+}
+
+export interface Scenario {
+  name: string;
+  steps: ScenarioStep[];
+  line: number;
+}
+
+export interface Requirement {
+  name: string;
+  description: string;
+  scenarios: Scenario[];
+  raw: string;
+  line: number;
+}
+
+export interface ParsedSpec {
+  title: string | null;
+  purpose: string | null;
+  requirements: Requirement[];
 }
 
 export interface SpecDetail {
@@ -57,6 +157,7 @@ export interface SpecDetail {
   sourceId: string;
   path: string;
   content: string;
+  parsed: ParsedSpec;
 }
 
 export interface Idea {
@@ -67,4 +168,143 @@ export interface Idea {
   description: string;
   createdAt: string;
   updatedAt: string;
+  /** Id of the change this idea was promoted into */
+  promotedTo: string | null;
+}
+
+export type RequirementChange = 'added' | 'modified' | 'removed' | 'renamed';
+
+export interface RequirementDiff {
+  name: string;
+  change: RequirementChange;
+  previousName: string | null;
+  before: string | null;
+  after: string | null;
+  /** Scenario-level changes of a modified requirement */
+  scenarios: ScenarioDiff[];
+}
+
+export interface ScenarioDiff {
+  name: string;
+  change: RequirementChange;
+  before: string | null;
+  after: string | null;
+}
+
+export interface SpecVersion {
+  commit: CommitInfo;
+  /** False when this commit deleted the spec */
+  exists: boolean;
+  requirements: RequirementDiff[];
+}
+
+export interface SpecRevision {
+  rev: string;
+  /** null for the working tree */
+  commit: CommitInfo | null;
+  exists: boolean;
+}
+
+export interface SpecDiff {
+  id: string;
+  path: string;
+  from: SpecRevision;
+  to: SpecRevision;
+  requirements: RequirementDiff[];
+  unifiedDiff: string;
+  additions: number;
+  deletions: number;
+}
+
+export interface MergedSpec {
+  capability: string;
+  path: string;
+  isNew: boolean;
+  content: string;
+  requirements: RequirementDiff[];
+  conflicts: string[];
+}
+
+export interface UpdateEvent<T = unknown> {
+  id: string;
+  sourceId: string | null;
+  previousId?: string;
+  data: T | null;
+}
+
+export interface SearchHighlight {
+  /** Character offsets into the snippet */
+  start: number;
+  end: number;
+}
+
+export interface SearchHit {
+  kind: 'change' | 'spec' | 'idea';
+  id: string;
+  sourceId: string;
+  title: string;
+  field: string;
+  score: number;
+  snippet: string;
+  highlights: SearchHighlight[];
+}
+
+export interface SearchResponse {
+  query: string;
+  total: number;
+  hits: SearchHit[];
+}
+
+export interface ArchivedChange {
+  change: Change;
+  specs: MergedSpec[];
+}
+
+export interface Diagnostic {
+  rule: string;
+  severity: 'error' | 'warning';
+  /** Path relative to the source root */
+  file: string;
+  line: number | null;
+  message: string;
+}
+
+export interface EntityDiagnostics {
+  id: string;
+  diagnostics: Diagnostic[];
+}
+
+export interface SourceDiagnostics {
+  sourceId: string;
+  errors: number;
+  warnings: number;
+  changes: EntityDiagnostics[];
+  specs: EntityDiagnostics[];
+}
+
+export interface ChangeProgress {
+  id: string;
+  name: string;
+  status: ChangeStatus;
+  tasksDone: number;
+  tasksTotal: number;
+  /** null when the change has no tasks */
+  percent: number | null;
+}
+
+export interface SourceReport {
+  sourceId: string;
+  name: string;
+  changes: Record<'draft' | 'todo' | 'inProgress' | 'done' | 'archived' | 'total', number>;
+  tasksDone: number;
+  tasksTotal: number;
+  percent: number | null;
+  active: ChangeProgress[];
+  recentlyArchived: { id: string; name: string; archivedOn: string }[];
+  newIdeas: { id: string; title: string; createdOn: string }[];
+}
+
+export interface StatusReport {
+  range: { from: string; to: string };
+  sources: SourceReport[];
 }